@@ -8,7 +8,18 @@ fn main() -> io::Result<()> {
         .compile_protos(&[
             "./vendor2/v2ray-core/app/observatory/command/command.proto",
             "./vendor2/v2ray-core/app/subscription/subscriptionmanager/command/command.proto",
-            "./vendor2/v2ray-core/app/router/command/command.proto"
+            "./vendor2/v2ray-core/app/router/command/command.proto",
+            "./vendor2/v2ray-core/app/proxyman/command/command.proto",
+            "./vendor2/v2ray-core/app/proxyman/config.proto",
+            "./vendor2/v2ray-core/transport/internet/tls/config.proto",
+            "./vendor2/v2ray-core/transport/internet/websocket/config.proto",
+            "./vendor2/v2ray-core/transport/internet/grpc/config.proto",
+            "./vendor2/v2ray-core/proxy/vmess/account.proto",
+            "./vendor2/v2ray-core/proxy/vmess/outbound/config.proto",
+            "./vendor2/v2ray-core/proxy/vless/account.proto",
+            "./vendor2/v2ray-core/proxy/vless/outbound/config.proto",
+            "./vendor2/v2ray-core/proxy/trojan/config.proto",
+            "./vendor2/v2ray-core/proxy/shadowsocks/config.proto"
         ],
                         &["./vendor2/protoc/include", "./vendor2/v2ray-core"])
         .expect("Failed to compile proto");
@@ -1,11 +1,15 @@
 use crate::app_ui::_Props::client_status;
 use crate::app_ui::main_page::PrimaryUI;
+use crate::app_ui::preferences::apply_theme;
 use crate::background::BackgroundWorker;
-use crate::client_status::{ClientStatus as AppClientStatus, ClientStatus};
+use crate::client_status::route::Route;
+use crate::client_status::{ClientStatus as AppClientStatus, ClientStatus, ClientStatusAction};
 use crate::grpc;
 use lazy_static::lazy_static;
 use std::ops::Deref;
 use std::sync::{Arc, Mutex, Once};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
 use yew::html::IntoPropValue;
 use yew::prelude::*;
@@ -67,6 +71,37 @@ pub fn app() -> Html {
         }
     }
 
+    {
+        // Links and `Route::navigate` only change the hash; this keeps `UIStatus.route` in step,
+        // including for back and forward.
+        let update_client_status = update_client_status.clone();
+        use_effect_with((), move |_| {
+            let listener = Closure::<dyn Fn()>::new(move || {
+                update_client_status.emit(ClientStatusAction::Navigate(Route::current()));
+            });
+            let window = web_sys::window();
+            if let Some(window) = &window {
+                let _ = window.add_event_listener_with_callback(
+                    "hashchange",
+                    listener.as_ref().unchecked_ref(),
+                );
+            }
+            move || {
+                if let Some(window) = &window {
+                    let _ = window.remove_event_listener_with_callback(
+                        "hashchange",
+                        listener.as_ref().unchecked_ref(),
+                    );
+                }
+            }
+        });
+    }
+
+    {
+        let theme = ui_managed_client_status_copy.preferences.theme;
+        use_effect_with(theme, move |theme| apply_theme(*theme));
+    }
+
     let onclick = Callback::from(move |_| {
         spawn_local(async {
             // grpc::connect().await;
@@ -0,0 +1,327 @@
+use crate::app_ui::time_format::{
+    format_duration_seconds, format_unix_time_ago, format_unix_time_local,
+};
+use crate::app_ui::Props;
+use crate::client_status::alerts::{Alert, AlertComparison, AlertMetric, AlertRule, AlertSettings};
+use crate::client_status::preferences::Preferences;
+use crate::client_status::{now_unix_seconds, ClientStatusAction};
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::{Badge, Button, ButtonGroup};
+use yew_bootstrap::util::Color;
+
+/// Resolved alerts listed on the page, newest first.
+const RESOLVED_ALERTS_SHOWN: usize = 20;
+
+fn event_value(event: &Event) -> Option<String> {
+    let target: Option<EventTarget> = event.target();
+    let target = target?;
+    if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
+        return Some(match input.type_().as_str() {
+            "checkbox" => input.checked().to_string(),
+            _ => input.value(),
+        });
+    }
+    target
+        .dyn_ref::<HtmlSelectElement>()
+        .map(|select| select.value())
+}
+
+fn alert_html(alert: &Alert) -> Html {
+    html! {
+        <li class={classes!("list-group-item")}>
+            <div>
+                {
+                    match alert.resolved_at {
+                        None => html! { <Badge class={"me-2"} style={Color::Danger}>{"Active"}</Badge> },
+                        Some(_) => html! { <Badge class={"me-2"} style={Color::Success}>{"Resolved"}</Badge> },
+                    }
+                }
+                <strong>{alert.rule_name.clone()}</strong>
+                <small class={classes!("text-muted", "ps-2")}>{alert.condition.clone()}</small>
+            </div>
+            <small class={classes!("text-muted")}>
+                <span title={format_unix_time_local(alert.started_at)}>
+                    {"Since "}{format_unix_time_ago(alert.started_at)}
+                </span>
+                {
+                    match alert.resolved_at {
+                        Some(resolved_at) => html! {
+                            <span title={format_unix_time_local(resolved_at)}>
+                                {", resolved "}{format_unix_time_ago(resolved_at)}
+                                {" after "}{format_duration_seconds(resolved_at - alert.started_at)}
+                            </span>
+                        },
+                        None => html! {},
+                    }
+                }
+                {", last reading "}{alert.last_reading.clone()}
+            </small>
+        </li>
+    }
+}
+
+#[function_component]
+pub fn AlertRulesEditorUI(props: &Props) -> Html {
+    let preferences = props.client_status.preferences.clone();
+    let alert_state = &props.client_status.core_link.alerts;
+    let subscription_names: Vec<String> = props
+        .client_status
+        .core_link
+        .fetched_subscription
+        .managed
+        .keys()
+        .cloned()
+        .collect();
+
+    let set_rules = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        move |rules: Vec<AlertRule>| {
+            update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                alerts: AlertSettings { rules },
+                ..preferences.clone()
+            }));
+        }
+    };
+
+    let update_rule = |index: usize, update: fn(&mut AlertRule, String)| {
+        let set_rules = set_rules.clone();
+        let rules = preferences.alerts.rules.clone();
+        Callback::from(move |event: Event| {
+            let Some(value) = event_value(&event) else {
+                return;
+            };
+            let mut rules = rules.clone();
+            if let Some(rule) = rules.get_mut(index) {
+                update(rule, value);
+            }
+            set_rules(rules);
+        })
+    };
+
+    let on_add_callback = {
+        let set_rules = set_rules.clone();
+        let settings = preferences.alerts.clone();
+        Callback::from(move |_| {
+            let mut rules = settings.rules.clone();
+            rules.push(AlertRule {
+                id: settings.next_rule_id(),
+                ..AlertRule::default()
+            });
+            set_rules(rules);
+        })
+    };
+
+    html! {
+        <Card class="my-3">
+            <CardHeader>{"Alert Rules"}</CardHeader>
+            <CardBody>
+                <p class={classes!("text-muted")}>
+                    {"A rule fires once its condition has held for the given time, and resolves once the reading has been back past the threshold by the clear margin for as long. Rules are checked after every poll."}
+                </p>
+                <table class={classes!("table", "table-sm", "align-middle")}>
+                    <thead>
+                        <tr>
+                            <th scope="col">{"On"}</th>
+                            <th scope="col">{"Name"}</th>
+                            <th scope="col">{"Metric"}</th>
+                            <th scope="col">{"Subscription"}</th>
+                            <th scope="col"></th>
+                            <th scope="col">{"Threshold"}</th>
+                            <th scope="col">{"For (s)"}</th>
+                            <th scope="col">{"Clear Margin"}</th>
+                            <th scope="col"></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                    {
+                        for preferences.alerts.rules.iter().enumerate().map(|(index, rule)| {
+                            let on_enabled_change = update_rule(index, |rule, value| rule.enabled = value == "true");
+                            let on_name_change = update_rule(index, |rule, value| rule.name = value);
+                            let on_metric_change = update_rule(index, |rule, value| {
+                                let subscription = match &rule.metric {
+                                    AlertMetric::AliveServers(subscription) => subscription.clone(),
+                                    _ => "".to_string(),
+                                };
+                                if let Some(metric) = AlertMetric::from_kind_name(&value, subscription) {
+                                    rule.metric = metric;
+                                }
+                            });
+                            let on_subscription_change = update_rule(index, |rule, value| {
+                                if let AlertMetric::AliveServers(_) = rule.metric {
+                                    rule.metric = AlertMetric::AliveServers(value);
+                                }
+                            });
+                            let on_comparison_change = update_rule(index, |rule, value| {
+                                if let Some(comparison) = AlertComparison::from_symbol(&value) {
+                                    rule.comparison = comparison;
+                                }
+                            });
+                            let on_threshold_change = update_rule(index, |rule, value| {
+                                if let Ok(threshold) = value.parse() {
+                                    rule.threshold = threshold;
+                                }
+                            });
+                            let on_for_change = update_rule(index, |rule, value| {
+                                if let Ok(for_seconds) = value.parse::<i64>() {
+                                    rule.for_seconds = for_seconds.max(0);
+                                }
+                            });
+                            let on_margin_change = update_rule(index, |rule, value| {
+                                if let Ok(clear_margin) = value.parse::<i64>() {
+                                    rule.clear_margin = clear_margin.max(0);
+                                }
+                            });
+                            let on_remove_callback = {
+                                let set_rules = set_rules.clone();
+                                let rules = preferences.alerts.rules.clone();
+                                Callback::from(move |_| {
+                                    let mut rules = rules.clone();
+                                    rules.remove(index);
+                                    set_rules(rules);
+                                })
+                            };
+                            let status = if alert_state.active.contains_key(&rule.id) {
+                                html! { <Badge style={Color::Danger}>{"Firing"}</Badge> }
+                            } else if let Some(since) = alert_state.pending_since(rule.id) {
+                                html! {
+                                    <Badge style={Color::Warning}>
+                                        {"Pending "}{format_duration_seconds(now_unix_seconds() - since)}
+                                    </Badge>
+                                }
+                            } else {
+                                html! {}
+                            };
+                            let subscription = match &rule.metric {
+                                AlertMetric::AliveServers(subscription) => Some(subscription.clone()),
+                                _ => None,
+                            };
+                            html! {
+                                <tr>
+                                    <td>
+                                        <input class={classes!("form-check-input")} type="checkbox"
+                                            checked={rule.enabled} onchange={on_enabled_change} />
+                                    </td>
+                                    <td>
+                                        <input class={classes!("form-control", "form-control-sm")} type="text"
+                                            value={rule.name.clone()} onchange={on_name_change} />
+                                    </td>
+                                    <td>
+                                        <select class={classes!("form-select", "form-select-sm")} onchange={on_metric_change}>
+                                        {
+                                            for AlertMetric::KIND_NAMES.iter().map(|name| html! {
+                                                <option value={*name} selected={*name == rule.metric.kind_name()}>{*name}</option>
+                                            })
+                                        }
+                                        </select>
+                                    </td>
+                                    <td>
+                                    {
+                                        match subscription {
+                                            Some(subscription) => html! {
+                                                <select class={classes!("form-select", "form-select-sm")} onchange={on_subscription_change}>
+                                                    <option value="" selected={subscription.is_empty()}>{"All subscriptions"}</option>
+                                                    {
+                                                        for subscription_names.iter().map(|name| html! {
+                                                            <option value={name.clone()} selected={*name == subscription}>{name.clone()}</option>
+                                                        })
+                                                    }
+                                                    {
+                                                        if !subscription.is_empty() && !subscription_names.contains(&subscription) {
+                                                            html! { <option value={subscription.clone()} selected=true>{subscription.clone()}{" (missing)"}</option> }
+                                                        } else {
+                                                            html! {}
+                                                        }
+                                                    }
+                                                </select>
+                                            },
+                                            None => html! {},
+                                        }
+                                    }
+                                    </td>
+                                    <td>
+                                        <select class={classes!("form-select", "form-select-sm")} onchange={on_comparison_change}>
+                                        {
+                                            for AlertComparison::ALL.iter().map(|comparison| html! {
+                                                <option value={comparison.symbol()} selected={*comparison == rule.comparison}>{comparison.symbol()}</option>
+                                            })
+                                        }
+                                        </select>
+                                    </td>
+                                    <td>
+                                        <input class={classes!("form-control", "form-control-sm")} type="number"
+                                            value={rule.threshold.to_string()} onchange={on_threshold_change} />
+                                    </td>
+                                    <td>
+                                        <input class={classes!("form-control", "form-control-sm")} type="number" min="0"
+                                            value={rule.for_seconds.to_string()} onchange={on_for_change} />
+                                    </td>
+                                    <td>
+                                        <input class={classes!("form-control", "form-control-sm")} type="number" min="0"
+                                            value={rule.clear_margin.to_string()} onchange={on_margin_change} />
+                                    </td>
+                                    <td>
+                                        <span class={classes!("me-1")}>{status}</span>
+                                        <button class={classes!("btn", "btn-outline-danger", "btn-sm")} type="button"
+                                            onclick={on_remove_callback}>{"Remove"}</button>
+                                    </td>
+                                </tr>
+                            }
+                        })
+                    }
+                    </tbody>
+                </table>
+                <ButtonGroup>
+                    <Button outline=true onclick={on_add_callback}>{"Add Rule"}</Button>
+                </ButtonGroup>
+            </CardBody>
+        </Card>
+    }
+}
+
+#[function_component]
+pub fn AlertsUI(props: &Props) -> Html {
+    let alert_state = &props.client_status.core_link.alerts;
+
+    html! {
+        <div>
+            <Card class="my-3">
+                <CardHeader>{"Active Alerts"}</CardHeader>
+                <CardBody>
+                {
+                    if alert_state.active.is_empty() {
+                        html! { <span class={classes!("text-muted")}>{"No alert is firing."}</span> }
+                    } else {
+                        html! {
+                            <ul class={classes!("list-group")}>
+                                { for alert_state.active.values().map(alert_html) }
+                            </ul>
+                        }
+                    }
+                }
+                </CardBody>
+            </Card>
+            <Card class="my-3">
+                <CardHeader>{"Resolved Alerts"}</CardHeader>
+                <CardBody>
+                {
+                    if alert_state.resolved.is_empty() {
+                        html! { <span class={classes!("text-muted")}>{"No alert has resolved since this page was opened."}</span> }
+                    } else {
+                        html! {
+                            <ul class={classes!("list-group")}>
+                                { for alert_state.resolved.iter().rev().take(RESOLVED_ALERTS_SHOWN).map(alert_html) }
+                            </ul>
+                        }
+                    }
+                }
+                </CardBody>
+            </Card>
+            <AlertRulesEditorUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        </div>
+    }
+}
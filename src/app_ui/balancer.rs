@@ -0,0 +1,131 @@
+use crate::app_ui::outbound_link::SubscriptionServerLink;
+use crate::app_ui::Props;
+use crate::client_status::core_link::CoreLinkAction;
+use crate::client_status::ClientStatusAction::{ApplyAction, SyncNow};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::{Badge, Button};
+use yew_bootstrap::util::Color;
+
+#[function_component]
+pub fn BalancerUI(props: &Props) -> Html {
+    let router_status = &props.client_status.core_link.fetched_router_status;
+    let measurement = &props.client_status.core_link.fetched_measurement;
+    let principle_targets = router_status.principle_targets();
+    let override_target = router_status.override_target();
+    let selected_target = router_status.selected_target();
+
+    let select_callback = |target: String| {
+        let update_client_status = props.update_client_status.clone();
+        Callback::from(move |_| {
+            let action = CoreLinkAction::SetPrimaryBalancerTarget(target.clone());
+            update_client_status.emit(ApplyAction(action));
+            update_client_status.emit(SyncNow());
+        })
+    };
+
+    html! {
+        <div class={classes!("my-3")}>
+            <Card class="mb-3">
+                <CardHeader>{"Balancer "}<code>{"subscriptions"}</code></CardHeader>
+                <CardBody>
+                    <div>
+                        <b class={classes!("pe-1")}>{"Strategy"}</b>
+                        // BalancerMsg only carries the override and the principle targets.
+                        <span class={classes!("text-muted")}>{"Not reported by the core"}</span>
+                    </div>
+                    <div>
+                        <b class={classes!("pe-1")}>{"Override"}</b>
+                        {
+                            match override_target.clone() {
+                                Some(target) => html! {
+                                    <>
+                                        <code class={classes!("pe-2")}>{target}</code>
+                                        <Button outline=true onclick={select_callback("".to_string())}>{"Clear Override"}</Button>
+                                    </>
+                                },
+                                None => html! { <span class={classes!("text-muted")}>{"None (automatic)"}</span> },
+                            }
+                        }
+                    </div>
+                    <div>
+                        <b class={classes!("pe-1")}>{"Selected"}</b>
+                        <code>{selected_target.clone().unwrap_or_default()}</code>
+                    </div>
+                </CardBody>
+            </Card>
+            <table class={classes!("table", "table-sm", "table-hover", "align-middle")}>
+                <thead>
+                    <tr>
+                        <th scope="col">{"Rank"}</th>
+                        <th scope="col">{"Outbound Tag"}</th>
+                        <th scope="col">{"Latency"}</th>
+                        <th scope="col">{"Subscription Server"}</th>
+                        <th scope="col"></th>
+                    </tr>
+                </thead>
+                <tbody>
+                {
+                    for principle_targets.iter().enumerate().map(|(rank, target)| {
+                        let is_selected = selected_target.as_ref() == Some(target);
+                        let is_override = override_target.as_ref() == Some(target);
+                        html! {
+                            <tr class={classes!(is_selected.then_some("table-active"))}>
+                                <td>{rank + 1}</td>
+                                <td>
+                                    <code class={classes!("pe-1")}>{target}</code>
+                                    {
+                                        if is_selected {
+                                            html! { <Badge class={"me-1"} style={Color::Success}>{"Selected"}</Badge> }
+                                        } else {
+                                            html! {}
+                                        }
+                                    }
+                                    {
+                                        if is_override {
+                                            html! { <Badge class={"me-1"} style={Color::Warning}>{"Override"}</Badge> }
+                                        } else {
+                                            html! {}
+                                        }
+                                    }
+                                </td>
+                                <td>
+                                {
+                                    match measurement.managed.get(target) {
+                                        Some(observation) if observation.alive => html! { <>{observation.delay}{" ms"}</> },
+                                        Some(_) => html! { <Badge style={Color::Danger}>{"ERROR"}</Badge> },
+                                        None => html! { <Badge style={Color::Secondary}>{"Unknown"}</Badge> },
+                                    }
+                                }
+                                </td>
+                                <td>
+                                    <SubscriptionServerLink client_status={props.client_status.clone()}
+                                        update_client_status={props.update_client_status.clone()}
+                                        outbound_tag={target.clone()} />
+                                </td>
+                                <td>
+                                {
+                                    if is_override {
+                                        html! {}
+                                    } else {
+                                        html! { <Button outline=true onclick={select_callback(target.clone())}>{"Select"}</Button> }
+                                    }
+                                }
+                                </td>
+                            </tr>
+                        }
+                    })
+                }
+                </tbody>
+            </table>
+            {
+                if principle_targets.is_empty() {
+                    html! { <div class={classes!("text-muted")}>{"The balancer has not reported any candidate yet"}</div> }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
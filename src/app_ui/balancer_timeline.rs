@@ -0,0 +1,87 @@
+use crate::app_ui::preferences::latency_color;
+use crate::app_ui::time_format::{format_unix_time_ago, format_unix_time_local};
+use crate::app_ui::Props;
+use crate::client_status::balancer_timeline::{BalancerSwitchKind, BalancerSwitchTarget};
+use crate::client_status::manual_override::OverrideOrigin;
+use crate::client_status::preferences::LatencyThresholds;
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::Badge;
+use yew_bootstrap::util::Color;
+
+fn switch_target_html(target: &BalancerSwitchTarget, thresholds: &LatencyThresholds) -> Html {
+    let Some(tag) = &target.tag else {
+        return html! { <span class={classes!("text-muted")}>{"none"}</span> };
+    };
+    html! {
+        <>
+            <code>{tag.clone()}</code>
+            {
+                match target.latency {
+                    Some(latency) => html! { <Badge class={"ms-1"} style={latency_color(thresholds, latency)}>{latency}{" ms"}</Badge> },
+                    None => html! { <Badge class={"ms-1"} style={Color::Danger}>{"Dead"}</Badge> },
+                }
+            }
+        </>
+    }
+}
+
+#[function_component]
+pub fn BalancerTimelineUI(props: &Props) -> Html {
+    let timeline = &props.client_status.core_link.balancer_timeline;
+    let thresholds = &props.client_status.preferences.latency_thresholds;
+
+    html! {
+        <Card class="my-3">
+            <CardHeader>{"Balancer Switches"}</CardHeader>
+            <CardBody>
+            {
+                if timeline.events.is_empty() {
+                    html! {
+                        <span class={classes!("text-muted")}>
+                            {"No switches since this page was opened. Changes of the principle target and the override are recorded here as they are polled."}
+                        </span>
+                    }
+                } else {
+                    html! {
+                        <ul class={classes!("list-unstyled", "mb-0", "border-start", "ps-3")}>
+                        {
+                            for timeline.events.iter().rev().map(|event| html! {
+                                <li class={classes!("mb-3")}>
+                                    <div>
+                                        <small class={classes!("text-muted", "pe-2")} title={format_unix_time_local(event.at)}>
+                                            {format_unix_time_ago(event.at)}
+                                        </small>
+                                        {
+                                            match event.kind {
+                                                BalancerSwitchKind::Automatic => html! { <Badge style={Color::Info}>{event.kind.label()}</Badge> },
+                                                BalancerSwitchKind::Override(Some(OverrideOrigin::User)) => html! { <Badge style={Color::Primary}>{event.kind.label()}</Badge> },
+                                                BalancerSwitchKind::Override(Some(_)) => html! { <Badge style={Color::Warning}>{event.kind.label()}</Badge> },
+                                                BalancerSwitchKind::Override(None) => html! { <Badge style={Color::Secondary}>{event.kind.label()}</Badge> },
+                                            }
+                                        }
+                                        {
+                                            if event.overridden {
+                                                html! { <small class={classes!("text-muted", "ps-2")}>{"behind an override, traffic did not move"}</small> }
+                                            } else {
+                                                html! {}
+                                            }
+                                        }
+                                    </div>
+                                    <div>
+                                        {switch_target_html(&event.previous, thresholds)}
+                                        {" \u{2192} "}
+                                        {switch_target_html(&event.new, thresholds)}
+                                    </div>
+                                </li>
+                            })
+                        }
+                        </ul>
+                    }
+                }
+            }
+            </CardBody>
+        </Card>
+    }
+}
@@ -0,0 +1,181 @@
+use crate::app_ui::time_format::format_unix_time_ago;
+use crate::app_ui::Props;
+use crate::client_status::bulk_operation::{BulkItemState, BulkOperation, BulkOperationKind};
+use crate::client_status::ui_status::UIStatus;
+use crate::client_status::{now_unix_seconds, ClientStatusAction};
+use std::collections::{BTreeMap, BTreeSet};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::Badge;
+use yew_bootstrap::util::Color;
+
+#[function_component]
+pub fn BulkOperationToolbarUI(props: &Props) -> Html {
+    let client_status = &props.client_status;
+    let managed = &client_status.core_link.fetched_subscription.managed;
+    let selected: BTreeSet<String> = client_status
+        .ui_status
+        .subscription_selected
+        .iter()
+        .filter(|name| managed.contains_key(*name))
+        .cloned()
+        .collect();
+    let is_running = client_status
+        .bulk_operation
+        .as_ref()
+        .is_some_and(|bulk_operation| !bulk_operation.is_finished());
+
+    let update_callback = |names: BTreeSet<String>| {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = client_status.ui_status.clone();
+        Callback::from(move |_| {
+            let items: BTreeMap<String, BulkItemState> = names
+                .iter()
+                .map(|name| (name.clone(), BulkItemState::Pending))
+                .collect();
+            update_client_status.emit(ClientStatusAction::StartBulkOperation(BulkOperation::new(
+                BulkOperationKind::Update,
+                items,
+                now_unix_seconds(),
+            )));
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_selected: BTreeSet::new(),
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    // Removals are confirmed first, in the same dialog as single removals.
+    let on_remove_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = client_status.ui_status.clone();
+        let selected = selected.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_remove_pending: selected.clone(),
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    let on_select_all_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = client_status.ui_status.clone();
+        let all: BTreeSet<String> = managed.keys().cloned().collect();
+        let all_selected = !all.is_empty() && selected.len() == all.len();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_selected: if all_selected { BTreeSet::new() } else { all.clone() },
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    html! {
+        <div class={classes!("d-flex", "align-items-center", "flex-wrap", "gap-2", "mb-2")}>
+            <button type="button" class={classes!("btn", "btn-sm", "btn-outline-secondary")}
+                disabled={managed.is_empty()} onclick={on_select_all_callback}>
+                {if !managed.is_empty() && selected.len() == managed.len() { "Select None" } else { "Select All" }}
+            </button>
+            <small class={classes!("text-muted")}>{format!("{} selected", selected.len())}</small>
+            <div class={classes!("ms-auto", "d-flex", "gap-2")}>
+                <button type="button" class={classes!("btn", "btn-sm", "btn-outline-primary")}
+                    disabled={is_running || selected.is_empty()}
+                    onclick={update_callback(selected.clone())}>{"Update Selected"}</button>
+                <button type="button" class={classes!("btn", "btn-sm", "btn-outline-primary")}
+                    disabled={is_running || managed.is_empty()}
+                    onclick={update_callback(managed.keys().cloned().collect())}>{"Update All"}</button>
+                <button type="button" class={classes!("btn", "btn-sm", "btn-outline-danger")}
+                    disabled={is_running || selected.is_empty()}
+                    onclick={on_remove_callback}>{"Remove Selected"}</button>
+            </div>
+        </div>
+    }
+}
+
+fn item_state_badge(state: &BulkItemState) -> Html {
+    match state {
+        BulkItemState::Pending => html! { <Badge style={Color::Secondary}>{"Waiting"}</Badge> },
+        BulkItemState::Running => html! { <Badge style={Color::Info}>{"Running"}</Badge> },
+        BulkItemState::Succeeded => html! { <Badge style={Color::Success}>{"Done"}</Badge> },
+        BulkItemState::Failed(_) => html! { <Badge style={Color::Danger}>{"Failed"}</Badge> },
+        BulkItemState::Skipped(_) => html! { <Badge style={Color::Warning}>{"Skipped"}</Badge> },
+    }
+}
+
+#[function_component]
+pub fn BulkOperationProgressUI(props: &Props) -> Html {
+    let Some(bulk_operation) = &props.client_status.bulk_operation else {
+        return html! {};
+    };
+    let summary = bulk_operation.summary();
+    let percent = match summary.total() {
+        0 => 100,
+        total => summary.done() * 100 / total,
+    };
+
+    let on_dismiss_callback = {
+        let update_client_status = props.update_client_status.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::DismissBulkOperation());
+        })
+    };
+
+    html! {
+        <Card class="mb-3">
+            <CardHeader>
+                <div class={classes!("d-flex", "align-items-center")}>
+                    <div class={classes!("w-100")}>
+                        {
+                            match bulk_operation.items.len() {
+                                1 => bulk_operation.kind.label().to_string(),
+                                _ => format!("Bulk {}", bulk_operation.kind.label()),
+                            }
+                        }
+                        <small class={classes!("text-muted", "ms-2")}>{"started "}{format_unix_time_ago(bulk_operation.started_at)}</small>
+                    </div>
+                    <button type="button" class={classes!("btn", "btn-sm", "btn-outline-secondary")}
+                        disabled={!bulk_operation.is_finished()} onclick={on_dismiss_callback}>{"Dismiss"}</button>
+                </div>
+            </CardHeader>
+            <CardBody>
+                <div class={classes!("progress", "mb-2")} role="progressbar" aria-valuemin="0" aria-valuemax="100"
+                    aria-valuenow={percent.to_string()}>
+                    <div class={classes!("progress-bar", if summary.failed > 0 { "bg-danger" } else { "bg-success" })}
+                        style={format!("width: {}%", percent)}></div>
+                </div>
+                <div class={classes!("mb-2")}>
+                {
+                    if bulk_operation.is_finished() {
+                        format!(
+                            "Finished: {} succeeded, {} failed, {} skipped.",
+                            summary.succeeded, summary.failed, summary.skipped
+                        )
+                    } else {
+                        format!("{} of {} done", summary.done(), summary.total())
+                    }
+                }
+                </div>
+                <ul class={classes!("list-unstyled", "small", "mb-0")}>
+                {
+                    for bulk_operation.items.iter().map(|(name, state)| html! {
+                        <li class={classes!("d-flex", "align-items-center", "gap-2")}>
+                            {item_state_badge(state)}
+                            <span>{name.clone()}</span>
+                            {
+                                match state {
+                                    BulkItemState::Failed(reason) | BulkItemState::Skipped(reason) => html! {
+                                        <span class={classes!("text-muted")}>{reason.clone()}</span>
+                                    },
+                                    _ => html! {},
+                                }
+                            }
+                        </li>
+                    })
+                }
+                </ul>
+            </CardBody>
+        </Card>
+    }
+}
@@ -0,0 +1,220 @@
+use crate::app_ui::outbound_link::SubscriptionServerLink;
+use crate::app_ui::preferences::latency_color;
+use crate::app_ui::settings::preset_color;
+use crate::app_ui::time_format::{format_duration_seconds, format_unix_time_ago};
+use crate::app_ui::Props;
+use crate::client_status::balancer_timeline::BalancerSwitchEvent;
+use crate::client_status::now_unix_seconds;
+use crate::client_status::route::Route;
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::Badge;
+use yew_bootstrap::util::Color;
+
+/// How many entries the expiry and switch cards list.
+const DASHBOARD_LIST_LIMIT: usize = 5;
+
+#[derive(Properties, PartialEq)]
+struct DashboardCardProps {
+    title: AttrValue,
+    route: Route,
+    children: Children,
+}
+
+#[function_component]
+fn DashboardCard(props: &DashboardCardProps) -> Html {
+    html! {
+        <div class={classes!("col-md-6", "col-xl-4")}>
+            <Card class="h-100">
+                <CardHeader>
+                    <div class={classes!("d-flex", "align-items-center")}>
+                        <span class={classes!("w-100")}>{props.title.clone()}</span>
+                        <a class={classes!("text-nowrap", "small")} href={props.route.to_hash()}>{"Details"}</a>
+                    </div>
+                </CardHeader>
+                <CardBody>
+                    { for props.children.iter() }
+                </CardBody>
+            </Card>
+        </div>
+    }
+}
+
+#[function_component]
+pub fn DashboardUI(props: &Props) -> Html {
+    let core_link = &props.client_status.core_link;
+    let preferences = &props.client_status.preferences;
+    let measurement = &core_link.fetched_measurement;
+    let now = now_unix_seconds();
+
+    let override_target = core_link.fetched_router_status.override_target();
+    let selected_target = core_link.fetched_router_status.selected_target();
+    let mode = match &override_target {
+        None => html! { <Badge style={Color::Success}>{"Automatic"}</Badge> },
+        Some(target) => match preferences.preset_for_target(target) {
+            Some(preset) => {
+                html! { <Badge style={preset_color(preset.color)}>{preset.label.clone()}</Badge> }
+            }
+            None => html! { <Badge style={Color::Primary}>{"Manual"}</Badge> },
+        },
+    };
+    let selected_latency = match selected_target
+        .as_ref()
+        .and_then(|target| measurement.managed.get(target))
+    {
+        Some(status) if status.alive => html! {
+            <Badge style={latency_color(&preferences.latency_thresholds, status.delay)}>{status.delay}{" ms"}</Badge>
+        },
+        Some(_) => html! { <Badge style={Color::Danger}>{"Dead"}</Badge> },
+        None => html! { <Badge style={Color::Secondary}>{"Not probed"}</Badge> },
+    };
+
+    let server_index = core_link.subscription_servers_by_outbound_tag();
+    let alive_servers = server_index
+        .keys()
+        .filter(|tag| {
+            measurement
+                .managed
+                .get(*tag)
+                .is_some_and(|status| status.alive)
+        })
+        .count();
+    let probed_servers = server_index
+        .keys()
+        .filter(|tag| measurement.managed.contains_key(*tag))
+        .count();
+
+    // The core reports no expiry, so this relies on the optional `subscription-userinfo` header,
+    // which the browser can only read when the provider exposes it to cross-origin requests.
+    let mut expiring: Vec<(String, i64)> = core_link
+        .fetched_documents
+        .managed
+        .iter()
+        .filter(|(name, _)| core_link.fetched_subscription.managed.contains_key(*name))
+        .filter_map(|(name, document)| Some((name.clone(), document.usage.as_ref()?.expires_at?)))
+        .collect();
+    expiring.sort_by_key(|(_, expires_at)| *expires_at);
+    expiring.truncate(DASHBOARD_LIST_LIMIT);
+
+    let switches: Vec<&BalancerSwitchEvent> = core_link
+        .balancer_timeline
+        .events
+        .iter()
+        .rev()
+        .take(DASHBOARD_LIST_LIMIT)
+        .collect();
+    let health = &core_link.connection_health;
+
+    html! {
+        <div class={classes!("row", "g-3", "my-1")}>
+            <DashboardCard title="Selected Outbound" route={Route::Balancer}>
+                <div class={classes!("mb-2")}>{mode}</div>
+                {
+                    match &selected_target {
+                        Some(target) => html! {
+                            <>
+                                <div><code>{target.clone()}</code>{" "}{selected_latency}</div>
+                                <small>
+                                    <SubscriptionServerLink client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()}
+                                        outbound_tag={target.clone()} />
+                                </small>
+                            </>
+                        },
+                        None => html! { <span class={classes!("text-muted")}>{"The balancer has not selected an outbound"}</span> },
+                    }
+                }
+            </DashboardCard>
+            <DashboardCard title="Servers" route={Route::Observatory}>
+                <div class={classes!("fs-4")}>{format!("{} / {}", alive_servers, server_index.len())}</div>
+                <small class={classes!("text-muted")}>
+                    {format!("alive across {} subscriptions, {} probed", core_link.fetched_subscription.managed.len(), probed_servers)}
+                </small>
+            </DashboardCard>
+            <DashboardCard title="Expiring Subscriptions" route={Route::Subscriptions}>
+            {
+                if expiring.is_empty() {
+                    html! { <span class={classes!("text-muted")}>{"No fetched subscription document reports an expiry date"}</span> }
+                } else {
+                    html! {
+                        <ul class={classes!("list-unstyled", "mb-0")}>
+                        {
+                            for expiring.iter().map(|(name, expires_at)| html! {
+                                <li>
+                                    <a href={Route::Subscription(name.clone()).to_hash()}>{name.clone()}</a>
+                                    {
+                                        if *expires_at <= now {
+                                            html! { <Badge class={"ms-2"} style={Color::Danger}>{"Expired"}</Badge> }
+                                        } else {
+                                            html! { <small class={classes!("text-muted", "ms-2")}>{format!("in {}", format_duration_seconds(expires_at - now))}</small> }
+                                        }
+                                    }
+                                </li>
+                            })
+                        }
+                        </ul>
+                    }
+                }
+            }
+            <small class={classes!("text-muted", "d-block", "mt-2")}>
+                {"Best effort: the core does not report expiry dates. They come from the subscription-userinfo header of documents this browser fetched, which most providers do not expose to other sites."}
+            </small>
+            </DashboardCard>
+            <DashboardCard title="Recent Switches" route={Route::Timeline}>
+            {
+                if switches.is_empty() {
+                    html! { <span class={classes!("text-muted")}>{"No balancer switches since this page was opened"}</span> }
+                } else {
+                    html! {
+                        <ul class={classes!("list-unstyled", "mb-0")}>
+                        {
+                            for switches.iter().map(|event| html! {
+                                <li>
+                                    <small class={classes!("text-muted", "pe-2")}>{format_unix_time_ago(event.at)}</small>
+                                    <code>{event.previous.tag.clone().unwrap_or_else(|| "none".to_string())}</code>
+                                    {" \u{2192} "}
+                                    <code>{event.new.tag.clone().unwrap_or_else(|| "none".to_string())}</code>
+                                    <small class={classes!("text-muted", "ps-1")}>{event.kind.label()}</small>
+                                </li>
+                            })
+                        }
+                        </ul>
+                    }
+                }
+            }
+            </DashboardCard>
+            <DashboardCard title="Connection" route={Route::Setting}>
+                <div class={classes!("mb-2")}>
+                {
+                    match (health.is_reachable(), health.last_success_at) {
+                        (true, _) => html! { <Badge style={Color::Success}>{"Connected"}</Badge> },
+                        (false, None) if health.consecutive_failures == 0 => html! { <Badge style={Color::Secondary}>{"Connecting"}</Badge> },
+                        (false, _) => html! { <Badge style={Color::Danger}>{"Unreachable"}</Badge> },
+                    }
+                }
+                </div>
+                <ul class={classes!("list-unstyled", "small", "mb-0")}>
+                    <li>{"Last answer: "}{format_unix_time_ago(health.last_success_at.unwrap_or(0))}</li>
+                    {
+                        if health.is_reachable() {
+                            html! { <li>{format!("Balancer query took {} ms", health.round_trip_ms)}</li> }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    {
+                        if health.consecutive_failures > 0 {
+                            html! {
+                                <li class={classes!("text-danger")}>
+                                    {format!("{} failed polls: {}", health.consecutive_failures, health.last_error)}
+                                </li>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </ul>
+            </DashboardCard>
+        </div>
+    }
+}
@@ -0,0 +1,133 @@
+use crate::app_ui::Props;
+use crate::client_status::outbound_config::parse_document_entries;
+use crate::client_status::ui_status::UIStatus;
+use crate::client_status::ClientStatusAction;
+use std::collections::BTreeMap;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlTextAreaElement};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::Badge;
+use yew_bootstrap::util::Color;
+
+/// Long entry names are cut so a rejected entry stays on one line.
+fn shorten_source(source: &str) -> String {
+    const MAX_CHARS: usize = 60;
+    if source.chars().count() <= MAX_CHARS {
+        return source.to_string();
+    }
+    format!("{}\u{2026}", source.chars().take(MAX_CHARS).collect::<String>())
+}
+
+#[function_component]
+pub fn SubscriptionDocumentPreviewUI(props: &Props) -> Html {
+    let ui_status = props.client_status.ui_status.clone();
+
+    let on_toggle_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_preview_open: !ui_status.subscription_preview_open,
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    if !ui_status.subscription_preview_open {
+        return html! {
+            <button type="button" class={classes!("btn", "btn-link", "px-0", "mb-3", "d-block")} onclick={on_toggle_callback}>
+                {"Preview a subscription document"}
+            </button>
+        };
+    }
+
+    let on_document_change_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        Callback::from(move |event: InputEvent| {
+            let target: Option<EventTarget> = event.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlTextAreaElement>().ok());
+            if let Some(input) = input {
+                update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                    subscription_preview_document: input.value(),
+                    ..ui_status.clone()
+                }));
+            }
+        })
+    };
+
+    let document = ui_status.subscription_preview_document.clone();
+    let preview = if document.trim().is_empty() {
+        None
+    } else {
+        Some(parse_document_entries(&document))
+    };
+
+    html! {
+        <div class={classes!("mb-3")}>
+            <div class={classes!("d-flex", "align-items-center", "mb-2")}>
+                <label class={classes!("form-label", "mb-0", "w-100")} for="subscription-preview-document">
+                    {"Subscription Document"}
+                </label>
+                <button type="button" class={classes!("btn", "btn-link", "btn-sm", "text-nowrap")} onclick={on_toggle_callback}>
+                    {"Close Preview"}
+                </button>
+            </div>
+            <textarea id="subscription-preview-document" class={classes!("form-control", "font-monospace", "mb-2")} rows="6"
+                placeholder="Paste base64 encoded share links, a SIP008 JSON document or a clash YAML proxies list"
+                value={document.clone()} oninput={on_document_change_callback} />
+            <small class={classes!("text-muted", "d-block", "mb-2")}>{"The document is only parsed here and is not sent to the core."}</small>
+            {
+                match preview {
+                    None => html! {},
+                    Some(Err(e)) => html! { <div class={classes!("text-danger")}>{"Cannot parse the document: "}{e}</div> },
+                    Some(Ok(preview)) => {
+                        let accepted: Vec<_> = preview.accepted().collect();
+                        let rejected: Vec<_> = preview.rejected().collect();
+                        let mut protocols: BTreeMap<&str, usize> = BTreeMap::new();
+                        for config in &accepted {
+                            *protocols.entry(config.protocol.as_str()).or_default() += 1;
+                        }
+                        html! {
+                            <>
+                                <div class={classes!("mb-2")}>
+                                    {format!("{}: {} servers", preview.format.label(), accepted.len())}
+                                    {
+                                        for protocols.iter().map(|(protocol, count)| html! {
+                                            <Badge class={"ms-1"} style={Color::Info}>{format!("{} {}", protocol, count)}</Badge>
+                                        })
+                                    }
+                                    {
+                                        if rejected.is_empty() {
+                                            html! {}
+                                        } else {
+                                            html! { <Badge class={"ms-1"} style={Color::Danger}>{format!("{} rejected", rejected.len())}</Badge> }
+                                        }
+                                    }
+                                </div>
+                                <ul class={classes!("list-unstyled", "small", "mb-0")}>
+                                {
+                                    for accepted.iter().map(|config| html! {
+                                        <li>
+                                            {if config.name.is_empty() { "(unnamed)".to_string() } else { config.name.clone() }}
+                                            <span class={classes!("text-muted")}>{format!(" {} {}:{}", config.protocol, config.address, config.port)}</span>
+                                        </li>
+                                    })
+                                }
+                                {
+                                    for rejected.iter().map(|(source, reason)| html! {
+                                        <li class={classes!("text-danger")}>
+                                            <code class={classes!("text-danger")}>{shorten_source(source)}</code>{": "}{reason.to_string()}
+                                        </li>
+                                    })
+                                }
+                                </ul>
+                            </>
+                        }
+                    }
+                }
+            }
+        </div>
+    }
+}
@@ -0,0 +1,161 @@
+use crate::app_ui::outbound_link::SubscriptionServerLink;
+use crate::app_ui::preferences::latency_color;
+use crate::app_ui::Props;
+use crate::client_status::outbound_config::group_duplicate_endpoints;
+use crate::client_status::ClientStatusAction;
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::Badge;
+use yew_bootstrap::util::Color;
+
+#[function_component]
+pub fn DuplicateServersUI(props: &Props) -> Html {
+    let core_link = &props.client_status.core_link;
+    let configs = core_link.outbound_configs_by_outbound_tag();
+    let clusters = group_duplicate_endpoints(&configs);
+    let failed_documents: Vec<_> = core_link
+        .fetched_documents
+        .managed
+        .iter()
+        .filter_map(|(name, document)| match &document.result {
+            Ok(_) => None,
+            Err(e) => Some(format!("{}: {}", name, e)),
+        })
+        .collect();
+    let unfetched: Vec<String> = core_link
+        .subscription_urls()
+        .into_keys()
+        .filter(|name| {
+            !core_link.fetched_documents.managed.contains_key(name)
+                && !core_link.fetched_documents.fetching.contains(name)
+        })
+        .collect();
+
+    let on_fetch_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let unfetched = unfetched.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::FetchSubscriptionDocuments(
+                unfetched.clone(),
+            ));
+        })
+    };
+
+    html! {
+        <Card class="mb-3">
+            <CardHeader>{"Duplicate Servers"}</CardHeader>
+            <CardBody>
+                <small class={classes!("text-muted", "d-block", "mb-2")}>
+                    {"Servers are compared using the subscription documents, which this browser fetches directly from each provider's URL, token included, rather than through the core and the subscription's import outbound. Providers that do not allow cross-origin requests cannot be compared."}
+                </small>
+                <small class={classes!("text-muted", "d-block", "mb-2")}>
+                    {format!(
+                        "Compared by protocol, address, port and user ID across {} decoded servers.",
+                        configs.len()
+                    )}
+                </small>
+                {
+                    if unfetched.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <div class={classes!("mb-2")}>
+                                <small class={classes!("text-muted", "me-2")}>
+                                    {format!("{} subscription documents have not been fetched.", unfetched.len())}
+                                </small>
+                                <button class={classes!("btn", "btn-outline-secondary", "btn-sm")} type="button"
+                                    onclick={on_fetch_callback}>{"Fetch Documents"}</button>
+                            </div>
+                        }
+                    }
+                }
+                {
+                    for failed_documents.iter().map(|failure| html! {
+                        <div class={classes!("text-warning")}><small>{failure.clone()}</small></div>
+                    })
+                }
+                {
+                    if configs.is_empty() {
+                        html! { <div>{"No servers could be compared yet. Fetch the subscription documents to look for duplicates."}</div> }
+                    } else if clusters.is_empty() && !unfetched.is_empty() {
+                        html! { <div>{"No duplicates among the fetched subscriptions. Subscriptions whose documents have not been fetched are not compared."}</div> }
+                    } else if clusters.is_empty() {
+                        html! { <div>{"No duplicates found"}</div> }
+                    } else {
+                        html! {
+                            <table class={classes!("table", "table-sm", "align-middle", "mb-0")}>
+                                <thead>
+                                    <tr>
+                                        <th scope="col">{"Endpoint"}</th>
+                                        <th scope="col">{"Server"}</th>
+                                        <th scope="col">{"Latency"}</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                {
+                                    for clusters.iter().map(|(endpoint, outbound_tags)| {
+                                        let fastest = outbound_tags
+                                            .iter()
+                                            .filter_map(|tag| core_link.fetched_measurement.managed.get(tag))
+                                            .filter(|status| status.alive)
+                                            .min_by_key(|status| status.delay)
+                                            .map(|status| status.outbound_tag.clone());
+                                        html! {
+                                            <>
+                                            {
+                                                for outbound_tags.iter().enumerate().map(|(index, outbound_tag)| {
+                                                    let observation = core_link.fetched_measurement.managed.get(outbound_tag);
+                                                    html! {
+                                                        <tr>
+                                                            {
+                                                                if index == 0 {
+                                                                    html! {
+                                                                        <td rowspan={outbound_tags.len().to_string()}>
+                                                                            <code>{format!("{} {}:{}", endpoint.protocol, endpoint.address, endpoint.port)}</code>
+                                                                        </td>
+                                                                    }
+                                                                } else {
+                                                                    html! {}
+                                                                }
+                                                            }
+                                                            <td>
+                                                                <SubscriptionServerLink client_status={props.client_status.clone()}
+                                                                    update_client_status={props.update_client_status.clone()}
+                                                                    outbound_tag={outbound_tag.clone()} />
+                                                            </td>
+                                                            <td>
+                                                            {
+                                                                match observation {
+                                                                    Some(observation) if observation.alive => html! {
+                                                                        <Badge class={"me-1"} style={latency_color(&props.client_status.preferences.latency_thresholds, observation.delay)}>{observation.delay}{"ms"}</Badge>
+                                                                    },
+                                                                    Some(_) => html! { <Badge class={"me-1"} style={Color::Danger}>{"Dead"}</Badge> },
+                                                                    None => html! { <Badge class={"me-1"} style={Color::Secondary}>{"Not probed"}</Badge> },
+                                                                }
+                                                            }
+                                                            {
+                                                                if fastest.as_ref() == Some(outbound_tag) {
+                                                                    html! { <Badge style={Color::Success}>{"Fastest"}</Badge> }
+                                                                } else {
+                                                                    html! {}
+                                                                }
+                                                            }
+                                                            </td>
+                                                        </tr>
+                                                    }
+                                                })
+                                            }
+                                            </>
+                                        }
+                                    })
+                                }
+                                </tbody>
+                            </table>
+                        }
+                    }
+                }
+            </CardBody>
+        </Card>
+    }
+}
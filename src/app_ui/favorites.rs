@@ -0,0 +1,149 @@
+use crate::app_ui::outbound_link::SubscriptionServerLink;
+use crate::app_ui::preferences::latency_color;
+use crate::app_ui::Props;
+use crate::client_status::core_link::CoreLinkAction;
+use crate::client_status::manual_override::OverrideDuration;
+use crate::client_status::ClientStatusAction::{ApplyAction, SyncNow};
+use crate::client_status::{ClientStatus, ClientStatusAction};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::{Badge, ListGroup, ListGroupItem};
+use yew_bootstrap::util::Color;
+
+#[derive(Properties, PartialEq)]
+pub struct FavoriteToggleButtonProps {
+    pub client_status: ClientStatus,
+    pub update_client_status: Callback<ClientStatusAction>,
+    pub outbound_tag: String,
+    pub display_name: String,
+}
+
+#[function_component]
+pub fn FavoriteToggleButton(props: &FavoriteToggleButtonProps) -> Html {
+    let preferences = &props.client_status.preferences;
+    let is_favorite = preferences.is_favorite(&props.outbound_tag);
+
+    let onclick = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        let outbound_tag = props.outbound_tag.clone();
+        let display_name = props.display_name.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetPreferences(
+                preferences.toggle_favorite(&outbound_tag, &display_name),
+            ));
+        })
+    };
+
+    html! {
+        <button type="button" {onclick}
+            class={classes!("btn", "btn-sm", "btn-link", "text-decoration-none", if is_favorite { "text-warning" } else { "text-secondary" })}
+            title={if is_favorite { "Remove from Favorites" } else { "Add to Favorites" }}>
+            {if is_favorite { "\u{2605}" } else { "\u{2606}" }}
+        </button>
+    }
+}
+
+#[function_component]
+pub fn FavoritesUI(props: &Props) -> Html {
+    let favorites = &props.client_status.preferences.favorites;
+    if favorites.is_empty() {
+        return html! {};
+    }
+    let core_link = &props.client_status.core_link;
+    let server_index = core_link.subscription_servers_by_outbound_tag();
+    // A favorite is only reported missing once every subscription has been loaded. Statuses that
+    // failed to refresh keep their previous value, and new ones stay unloaded until fetched.
+    let subscriptions_loaded = core_link.fetched_router_status.managed.is_some()
+        && core_link.fetched_subscription.listed
+        && core_link
+            .fetched_subscription
+            .managed
+            .values()
+            .all(|subscription| subscription.is_some());
+    let selected_target = core_link.fetched_router_status.selected_target();
+
+    let select_callback = |target: String| {
+        let update_client_status = props.update_client_status.clone();
+        Callback::from(move |_| {
+            let action = CoreLinkAction::SetPrimaryBalancerTarget(target.clone());
+            update_client_status.emit(ApplyAction(action));
+            update_client_status.emit(ClientStatusAction::SetOverrideExpiry(
+                OverrideDuration::UntilChanged.expiry_for(&target),
+            ));
+            update_client_status.emit(SyncNow());
+        })
+    };
+
+    html! {
+        <Card class="mb-3">
+            <CardHeader>{"Favorites"}</CardHeader>
+            <ListGroup>
+            {
+                for favorites.iter().map(|favorite| {
+                    let server_ref = server_index.get(&favorite.outbound_tag);
+                    let observation = core_link.fetched_measurement.managed.get(&favorite.outbound_tag);
+                    let is_selected = selected_target.as_deref() == Some(favorite.outbound_tag.as_str());
+                    let display_name = match server_ref {
+                        Some(server_ref) => server_ref.display_name.clone(),
+                        None => favorite.display_name.clone(),
+                    };
+                    html_nested! {
+                        <ListGroupItem>
+                            <div class={classes!("d-flex", "align-items-center")}>
+                                <FavoriteToggleButton client_status={props.client_status.clone()}
+                                    update_client_status={props.update_client_status.clone()}
+                                    outbound_tag={favorite.outbound_tag.clone()}
+                                    display_name={display_name.clone()} />
+                                <div class={classes!("w-100")}>
+                                    <b class={classes!("pe-2")}>{display_name.clone()}</b>
+                                    <small class={classes!("text-muted", "pe-2")}>{favorite.outbound_tag.clone()}</small>
+                                    {
+                                        match (server_ref, observation) {
+                                            (None, _) if subscriptions_loaded => html! {
+                                                <Badge class={"me-1"} style={Color::Danger}>{"No longer provided"}</Badge>
+                                            },
+                                            (None, _) => html! {
+                                                <Badge class={"me-1"} style={Color::Secondary}>{"Loading"}</Badge>
+                                            },
+                                            (Some(_), Some(observation)) if observation.alive => html! {
+                                                <Badge class={"me-1"} style={latency_color(&props.client_status.preferences.latency_thresholds, observation.delay)}>{observation.delay}{"ms"}</Badge>
+                                            },
+                                            (Some(_), Some(_)) => html! {
+                                                <Badge class={"me-1"} style={Color::Danger}>{"ERROR"}</Badge>
+                                            },
+                                            (Some(_), None) => html! {
+                                                <Badge class={"me-1"} style={Color::Secondary}>{"Unknown"}</Badge>
+                                            },
+                                        }
+                                    }
+                                    {
+                                        if is_selected {
+                                            html! { <Badge class={"me-1"} style={Color::Success}>{"Selected"}</Badge> }
+                                        } else {
+                                            html! {}
+                                        }
+                                    }
+                                    <div>
+                                        <small>
+                                            <SubscriptionServerLink client_status={props.client_status.clone()}
+                                                update_client_status={props.update_client_status.clone()}
+                                                outbound_tag={favorite.outbound_tag.clone()} />
+                                        </small>
+                                    </div>
+                                </div>
+                                <div class={classes!("flex-shrink-1")}>
+                                    <button class={classes!("btn", "btn-outline-primary", "btn-sm")} type="button"
+                                        disabled={server_ref.is_none() || is_selected}
+                                        onclick={select_callback(favorite.outbound_tag.clone())}>{"Select"}</button>
+                                </div>
+                            </div>
+                        </ListGroupItem>
+                    }
+                })
+            }
+            </ListGroup>
+        </Card>
+    }
+}
@@ -1,12 +1,19 @@
 use super::Props;
-use crate::{client_status::ui_status::UIStatus,client_status::ClientStatusAction};
+use crate::client_status::route::Route;
 use std::ops::Deref;
 use yew::prelude::*;
 use yew::{function_component, html, props, Html, Properties};
 use yew_bootstrap::component::{BrandType, NavBar, NavDropdownItem, NavItem};
 
+use crate::app_ui::alerts::AlertsUI;
+use crate::app_ui::balancer::BalancerUI;
+use crate::app_ui::balancer_timeline::BalancerTimelineUI;
+use crate::app_ui::dashboard::DashboardUI;
+use crate::app_ui::observatory::ObservatoryUI;
+use crate::app_ui::policy::PolicyUI;
 use crate::app_ui::settings::SettingsUI;
 use crate::app_ui::subscription_list::SubscriptionListUI;
+use crate::app_ui::watchdog::WatchdogNoticeUI;
 
 #[function_component]
 pub fn PrimaryUI(props: &Props) -> Html {
@@ -15,58 +22,55 @@ pub fn PrimaryUI(props: &Props) -> Html {
         url: Some(AttrValue::from("")),
     };
 
-    let client_status = props.client_status.clone();
-    let update_client_status = props.update_client_status.clone();
-    let ui_status = client_status.ui_status.clone();
-
-    let click_on_subscription = {
-        let ui_status = client_status.ui_status.clone();
-        Callback::from(move |_| {
-        update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
-            active_window: "Subscription".to_string(),
-            ..ui_status.clone()
-        }));
-    })
+    let page = props.client_status.ui_status.route.page();
+    let active_alerts = props.client_status.core_link.alerts.active.len();
+    let alerts_nav_text = match active_alerts {
+        0 => "Alerts".to_string(),
+        count => format!("Alerts ({})", count),
     };
 
-    let client_status = props.client_status.clone();
-    let update_client_status = props.update_client_status.clone();
-
-    let click_on_setting = {
-        let ui_status = client_status.ui_status.clone();
-        Callback::from(move |_| {
-        update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
-            active_window: "Setting".to_string(),
-            ..ui_status.clone()
-        }));
-    })};
-
-    if props.client_status.ui_status.active_window.clone().eq("") {
-        let update_client_status = props.update_client_status.clone();
-        update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
-            active_window: "Setting".to_string(),
-            ..ui_status.clone()
-        }));
-        return html! { <>{"Loading"}</> };
-    }
-
     html! {
         <div>
-            <div class={classes!("d-none")} >{"active_UI="}{props.client_status.ui_status.active_window.clone()} </div>
+            <div class={classes!("d-none")} >{"active_UI="}{props.client_status.ui_status.route.to_hash()} </div>
             <div class={classes!("border-bottom")}>
                 <NavBar nav_id={"test-nav"} class="navbar-expand-lg navbar-light bg-light" brand={brand}>
+                    <NavItem text="Dashboard"
+                        active={page == Route::Dashboard}
+                        url={Route::Dashboard.to_hash()}/>
                     <NavItem text="Subscription"
-                        active={props.client_status.ui_status.active_window.clone().eq("Subscription")}
-                        onclick={click_on_subscription} url="#"/>
+                        active={page == Route::Subscriptions}
+                        url={Route::Subscriptions.to_hash()}/>
+                    <NavItem text="Observatory"
+                        active={page == Route::Observatory}
+                        url={Route::Observatory.to_hash()}/>
+                    <NavItem text="Balancer"
+                        active={page == Route::Balancer}
+                        url={Route::Balancer.to_hash()}/>
+                    <NavItem text="Timeline"
+                        active={page == Route::Timeline}
+                        url={Route::Timeline.to_hash()}/>
+                    <NavItem text={alerts_nav_text}
+                        active={page == Route::Alerts}
+                        url={Route::Alerts.to_hash()}/>
+                    <NavItem text="Policy"
+                        active={page == Route::Policy}
+                        url={Route::Policy.to_hash()}/>
                 <NavItem text="Setting"
-                    active={props.client_status.ui_status.active_window.clone().eq("Setting")}
-                    onclick={click_on_setting} url="#"/>
+                    active={page == Route::Setting}
+                    url={Route::Setting.to_hash()}/>
                 </NavBar>
             </div>
-            {match props.client_status.ui_status.active_window.as_str() {
-                "Subscription" => html! { <SubscriptionListUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} /> },
-                "Setting" => html! { <SettingsUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} /> },
-                _ => html! { <div>{"Loading"}</div> }
+            <WatchdogNoticeUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+            {match page {
+                Route::Dashboard => html! { <DashboardUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} /> },
+                Route::Subscriptions => html! { <SubscriptionListUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} /> },
+                Route::Observatory => html! { <ObservatoryUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} /> },
+                Route::Balancer => html! { <BalancerUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} /> },
+                Route::Timeline => html! { <BalancerTimelineUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} /> },
+                Route::Alerts => html! { <AlertsUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} /> },
+                Route::Policy => html! { <PolicyUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} /> },
+                Route::Setting => html! { <SettingsUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} /> },
+                _ => html! {}
             }}
         </div>
     }
@@ -0,0 +1,278 @@
+use crate::app_ui::time_format::format_unix_time_ago;
+use crate::app_ui::Props;
+use crate::client_status::outbound_config::{parse_share_link, OutboundConfig};
+use crate::client_status::outbound_handler::{build_outbound_handler_config, manual_outbound_tag};
+use crate::client_status::preferences::ManualOutbound;
+use crate::client_status::ui_status::UIStatus;
+use crate::client_status::{now_unix_seconds, ClientStatusAction};
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::{Badge, ListGroup, ListGroupItem};
+use yew_bootstrap::util::Color;
+
+fn describe_outbound_config(config: &OutboundConfig) -> String {
+    format!(
+        "{} {}:{} over {}{}",
+        config.protocol,
+        config.address,
+        config.port,
+        config.transport,
+        if config.security.is_empty() {
+            "".to_string()
+        } else {
+            format!(" + {}", config.security)
+        }
+    )
+}
+
+/// Parses the link and checks that it can be turned into an outbound handler config.
+fn prepare_outbound(link: &str, name: &str) -> Result<(String, OutboundConfig), String> {
+    let config = parse_share_link(link.trim())?;
+    let name = match name.trim() {
+        "" => config.name.as_str(),
+        name => name,
+    };
+    if name.trim().is_empty() {
+        return Err("the link carries no name, please enter one".to_string());
+    }
+    let tag = manual_outbound_tag(name);
+    build_outbound_handler_config(&tag, &config)?;
+    Ok((tag, config))
+}
+
+#[function_component]
+pub fn ManualOutboundAddUI(props: &Props) -> Html {
+    let ui_status = props.client_status.ui_status.clone();
+    if !ui_status.manual_outbound_card_open {
+        return html! {};
+    }
+    let link = ui_status.manual_outbound_new_link.clone();
+    let name = ui_status.manual_outbound_new_name.clone();
+    let prepared = if link.trim().is_empty() {
+        None
+    } else {
+        Some(prepare_outbound(&link, &name))
+    };
+    let tag_in_use = match &prepared {
+        Some(Ok((tag, _))) => {
+            props
+                .client_status
+                .core_link
+                .reported_outbound_tags()
+                .contains(tag)
+                || props
+                    .client_status
+                    .preferences
+                    .manual_outbounds
+                    .iter()
+                    .any(|manual_outbound| &manual_outbound.outbound_tag == tag)
+        }
+        _ => false,
+    };
+
+    let on_link_change_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        Callback::from(move |event: InputEvent| {
+            let target: Option<EventTarget> = event.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlTextAreaElement>().ok());
+            if let Some(input) = input {
+                update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                    manual_outbound_new_link: input.value(),
+                    ..ui_status.clone()
+                }));
+            }
+        })
+    };
+
+    let on_name_change_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        Callback::from(move |event: InputEvent| {
+            let target: Option<EventTarget> = event.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                    manual_outbound_new_name: input.value(),
+                    ..ui_status.clone()
+                }));
+            }
+        })
+    };
+
+    let on_submit_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let prepared = prepared.clone();
+        let link = link.clone();
+        Callback::from(move |_| {
+            let Some(Ok((tag, _))) = prepared.clone() else {
+                return;
+            };
+            // The form is cleared once the core accepts the outbound.
+            update_client_status.emit(ClientStatusAction::AddManualOutbound(ManualOutbound {
+                outbound_tag: tag,
+                share_link: link.trim().to_string(),
+                added_at: now_unix_seconds(),
+            }));
+        })
+    };
+    let error = ui_status
+        .manual_outbound_error
+        .as_ref()
+        .filter(|(tag, _)| matches!(&prepared, Some(Ok((prepared_tag, _))) if prepared_tag == tag))
+        .map(|(_, e)| e.clone());
+
+    html! {
+        <Card class="mb-3">
+            <CardHeader>{"Add Outbound from Link"}</CardHeader>
+            <CardBody>
+                <label class={classes!("form-label")} for="manual-outbound-link">{"Share Link"}</label>
+                <textarea id="manual-outbound-link" class={classes!("form-control", "font-monospace", "mb-3")} rows="3"
+                    placeholder="vmess://, vless://, trojan:// or ss://"
+                    value={link.clone()} oninput={on_link_change_callback} />
+                <label class={classes!("form-label")} for="manual-outbound-name">{"Name"}</label>
+                <input id="manual-outbound-name" type="text" class={classes!("form-control", "mb-3")}
+                    placeholder="Taken from the link when empty"
+                    value={name.clone()} oninput={on_name_change_callback} />
+                {
+                    match &prepared {
+                        None => html! {},
+                        Some(Ok((tag, config))) => html! {
+                            <div class={classes!("mb-3")}>
+                                <div>{"Outbound tag: "}<code>{tag.clone()}</code></div>
+                                <div>{describe_outbound_config(config)}</div>
+                                {
+                                    if tag_in_use {
+                                        html! { <div class={classes!("text-warning")}><small>{"An outbound with this tag already exists. Remove it first or enter another name."}</small></div> }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                            </div>
+                        },
+                        Some(Err(e)) => html! {
+                            <div class={classes!("text-danger", "mb-3")}>{"Cannot add this link: "}{e.clone()}</div>
+                        },
+                    }
+                }
+                {
+                    match error {
+                        Some(e) => html! { <div class={classes!("text-danger", "mb-3")}>{"The core refused the outbound: "}{e}</div> },
+                        None => html! {},
+                    }
+                }
+                <button type="button" class={classes!("btn", "btn-primary")}
+                    disabled={!matches!(prepared, Some(Ok(_))) || tag_in_use} onclick={on_submit_callback}>{"Add Outbound"}</button>
+            </CardBody>
+        </Card>
+    }
+}
+
+#[function_component]
+pub fn ManualOutboundsUI(props: &Props) -> Html {
+    let manual_outbounds = &props.client_status.preferences.manual_outbounds;
+    if manual_outbounds.is_empty() {
+        return html! {};
+    }
+    let reported_outbound_tags = props.client_status.core_link.reported_outbound_tags();
+    let ui_status_error = &props.client_status.ui_status.manual_outbound_error;
+    let ui_status_remove_error = &props.client_status.ui_status.manual_outbound_remove_error;
+
+    html! {
+        <Card class="mb-3">
+            <CardHeader>{"Outbounds Added from Links"}</CardHeader>
+            <CardBody>
+                <ListGroup>
+                {
+                    for manual_outbounds.iter().map(|manual_outbound| {
+                        let config = parse_share_link(&manual_outbound.share_link);
+                        let is_reported = reported_outbound_tags.contains(&manual_outbound.outbound_tag);
+                        let on_readd_callback = {
+                            let update_client_status = props.update_client_status.clone();
+                            let manual_outbound = manual_outbound.clone();
+                            Callback::from(move |_| {
+                                update_client_status.emit(ClientStatusAction::AddManualOutbound(
+                                    manual_outbound.clone(),
+                                ));
+                            })
+                        };
+                        let error = ui_status_error
+                            .as_ref()
+                            .filter(|(tag, _)| tag == &manual_outbound.outbound_tag)
+                            .map(|(_, e)| e.clone());
+                        let remove_error = ui_status_remove_error
+                            .as_ref()
+                            .filter(|(tag, _)| tag == &manual_outbound.outbound_tag)
+                            .map(|(_, e)| e.clone());
+                        let on_remove_callback = {
+                            let update_client_status = props.update_client_status.clone();
+                            let outbound_tag = manual_outbound.outbound_tag.clone();
+                            Callback::from(move |_| {
+                                update_client_status.emit(ClientStatusAction::RemoveManualOutbound(
+                                    outbound_tag.clone(),
+                                ));
+                            })
+                        };
+                        html_nested! {
+                            <ListGroupItem>
+                                <div class={classes!("d-flex", "align-items-center")}>
+                                    <div class={classes!("w-100")}>
+                                        <div>
+                                            <code class={classes!("me-2")}>{manual_outbound.outbound_tag.clone()}</code>
+                                            {
+                                                if is_reported {
+                                                    html! { <Badge style={Color::Success}>{"Reported"}</Badge> }
+                                                } else {
+                                                    html! { <Badge style={Color::Warning}>{"Not reported by the core"}</Badge> }
+                                                }
+                                            }
+                                        </div>
+                                        <small class={classes!("text-muted")}>
+                                            {
+                                                match &config {
+                                                    Ok(config) => describe_outbound_config(config),
+                                                    Err(e) => format!("Stored link is unreadable: {}", e),
+                                                }
+                                            }
+                                            {", added "}{format_unix_time_ago(manual_outbound.added_at)}
+                                        </small>
+                                        {
+                                            match error {
+                                                Some(e) => html! { <div class={classes!("text-danger")}><small>{"Re-adding failed: "}{e}</small></div> },
+                                                None => html! {},
+                                            }
+                                        }
+                                        {
+                                            match remove_error {
+                                                Some(e) => html! { <div class={classes!("text-danger")}><small>{"Removing failed: "}{e}</small></div> },
+                                                None => html! {},
+                                            }
+                                        }
+                                    </div>
+                                    <div class={classes!("flex-shrink-0")}>
+                                        {
+                                            if is_reported {
+                                                html! {}
+                                            } else {
+                                                html! {
+                                                    <button type="button" class={classes!("btn", "btn-sm", "btn-outline-primary", "me-2")}
+                                                        disabled={config.is_err()} onclick={on_readd_callback}>{"Re-add"}</button>
+                                                }
+                                            }
+                                        }
+                                        <button type="button" class={classes!("btn", "btn-sm", "btn-outline-danger")}
+                                            onclick={on_remove_callback}>{"Remove"}</button>
+                                    </div>
+                                </div>
+                            </ListGroupItem>
+                        }
+                    })
+                }
+                </ListGroup>
+            </CardBody>
+        </Card>
+    }
+}
@@ -1,6 +1,28 @@
 mod subscription_list;
+mod bulk_operation;
+mod subscription_backup;
+mod subscription_refresh;
+mod subscription_remove;
 pub(crate) mod main_page;
+mod dashboard;
 mod settings;
+pub(crate) mod preferences;
+mod observatory;
+mod duplicates;
+mod document_preview;
+mod balancer;
+mod balancer_timeline;
+mod alerts;
+mod outbound_link;
+mod outbound_details;
+mod server_export;
+mod manual_outbounds;
+mod notifications;
+mod favorites;
+mod time_format;
+mod watchdog;
+mod policy;
+mod schedule;
 
 use crate::client_status::{ClientStatus, ClientStatusAction};
 use yew::prelude::*;
@@ -0,0 +1,157 @@
+use crate::app_ui::Props;
+use crate::client_status::notifications::{
+    notification_permission, show_notification, Notice, NotificationKind, NotificationSettings,
+};
+use crate::client_status::preferences::Preferences;
+use crate::client_status::ClientStatusAction;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{EventTarget, HtmlInputElement, Notification, NotificationPermission};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+
+fn checkbox_checked(event: &Event) -> Option<bool> {
+    let target: Option<EventTarget> = event.target();
+    target
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .map(|input| input.checked())
+}
+
+#[function_component]
+pub fn NotificationSettingsUI(props: &Props) -> Html {
+    let preferences = props.client_status.preferences.clone();
+    let settings = preferences.notifications.clone();
+    // The permission lives in the browser, so it is read again after asking for it.
+    let permission = use_state(notification_permission);
+
+    let update_settings = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        move |notifications: NotificationSettings| {
+            update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                notifications,
+                ..preferences.clone()
+            }));
+        }
+    };
+
+    let on_enabled_change = {
+        let update_settings = update_settings.clone();
+        let settings = settings.clone();
+        let permission = permission.clone();
+        Callback::from(move |event: Event| {
+            let Some(enabled) = checkbox_checked(&event) else {
+                return;
+            };
+            if !enabled || *permission == Some(NotificationPermission::Granted) {
+                update_settings(NotificationSettings {
+                    enabled,
+                    ..settings.clone()
+                });
+                return;
+            }
+            let Ok(request) = Notification::request_permission() else {
+                return;
+            };
+            let update_settings = update_settings.clone();
+            let settings = settings.clone();
+            let permission = permission.clone();
+            spawn_local(async move {
+                let _ = JsFuture::from(request).await;
+                let granted = notification_permission();
+                permission.set(granted);
+                if granted == Some(NotificationPermission::Granted) {
+                    update_settings(NotificationSettings {
+                        enabled: true,
+                        ..settings.clone()
+                    });
+                }
+            });
+        })
+    };
+
+    let on_test_callback = Callback::from(|_| {
+        show_notification(&Notice {
+            kind: NotificationKind::SelectedOutboundDead,
+            key: "test".to_string(),
+            title: "Rendezvous".to_string(),
+            body: "Notifications are working".to_string(),
+        });
+    });
+
+    let permission_note = match *permission {
+        None => {
+            Some("This browser does not offer notifications here; they need a secure (https) page.")
+        }
+        Some(NotificationPermission::Denied) => {
+            Some("Notifications are blocked for this site in the browser settings.")
+        }
+        Some(NotificationPermission::Default) if settings.enabled => {
+            Some("Permission has not been granted yet.")
+        }
+        _ => None,
+    };
+
+    html! {
+        <Card class="mt-3">
+            <CardHeader>{"Notifications"}</CardHeader>
+            <CardBody>
+                <div class={classes!("form-check", "form-switch", "mb-3")}>
+                    <input class={classes!("form-check-input")} type="checkbox" id="notifications-enabled"
+                        disabled={permission.is_none()}
+                        checked={settings.enabled} onchange={on_enabled_change} />
+                    <label class={classes!("form-check-label")} for="notifications-enabled">
+                        {"Show browser notifications while this page is open"}
+                    </label>
+                </div>
+                {
+                    for NotificationKind::ALL.iter().map(|kind| {
+                        let kind = *kind;
+                        let on_kind_change = {
+                            let update_settings = update_settings.clone();
+                            let settings = settings.clone();
+                            Callback::from(move |event: Event| {
+                                if let Some(checked) = checkbox_checked(&event) {
+                                    update_settings(settings.with_kind(kind, checked));
+                                }
+                            })
+                        };
+                        let id = format!("notifications-kind-{:?}", kind);
+                        html! {
+                            <div class={classes!("form-check")}>
+                                <input class={classes!("form-check-input")} type="checkbox" id={id.clone()}
+                                    disabled={!settings.enabled}
+                                    checked={settings.kinds.contains(&kind)} onchange={on_kind_change} />
+                                <label class={classes!("form-check-label")} for={id}>{kind.label()}</label>
+                            </div>
+                        }
+                    })
+                }
+                <small class={classes!("text-muted", "d-block", "mt-2")}>
+                    {"Each notification is shown once per ten minutes, and at most five every five minutes."}
+                </small>
+                {
+                    match permission_note {
+                        Some(note) => html! { <small class={classes!("text-warning", "d-block")}>{note}</small> },
+                        None => html! {},
+                    }
+                }
+                {
+                    if props.client_status.core_link.notifications.suppressed > 0 {
+                        html! {
+                            <small class={classes!("text-muted", "d-block")}>
+                                {format!("{} notifications were held back by the rate limit.", props.client_status.core_link.notifications.suppressed)}
+                            </small>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                <button class={classes!("btn", "btn-outline-secondary", "btn-sm", "mt-2")} type="button"
+                    disabled={*permission != Some(NotificationPermission::Granted)}
+                    onclick={on_test_callback}>{"Send Test Notification"}</button>
+            </CardBody>
+        </Card>
+    }
+}
@@ -0,0 +1,135 @@
+use crate::app_ui::duplicates::DuplicateServersUI;
+use crate::app_ui::outbound_link::SubscriptionServerLink;
+use crate::app_ui::time_format::format_unix_time_ago;
+use crate::app_ui::Props;
+use crate::client_status::ui_status::{ObservatorySortKey, UIStatus};
+use crate::client_status::ClientStatusAction;
+use crate::grpc::proto::v2ray::core::app::observatory::OutboundStatus;
+use std::cmp::Ordering;
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::Badge;
+use yew_bootstrap::util::Color;
+
+fn compare_outbound_status(
+    a: &OutboundStatus,
+    b: &OutboundStatus,
+    sort_key: ObservatorySortKey,
+) -> Ordering {
+    let ordering = match sort_key {
+        ObservatorySortKey::OutboundTag => Ordering::Equal,
+        ObservatorySortKey::Alive => b.alive.cmp(&a.alive),
+        // Dead outbounds report a meaningless delay, keep them after the live ones.
+        ObservatorySortKey::Delay => (!a.alive, a.delay).cmp(&(!b.alive, b.delay)),
+        ObservatorySortKey::LastSeen => b.last_seen_time.cmp(&a.last_seen_time),
+        ObservatorySortKey::LastTry => b.last_try_time.cmp(&a.last_try_time),
+    };
+    ordering.then_with(|| a.outbound_tag.cmp(&b.outbound_tag))
+}
+
+#[function_component]
+pub fn ObservatoryUI(props: &Props) -> Html {
+    let ui_status = props.client_status.ui_status.clone();
+    let sort_key = ui_status.observatory_sort_key;
+    let sort_descending = ui_status.observatory_sort_descending;
+
+    let mut outbound_statuses: Vec<OutboundStatus> = props
+        .client_status
+        .core_link
+        .fetched_measurement
+        .managed
+        .values()
+        .cloned()
+        .collect();
+    outbound_statuses.sort_by(|a, b| {
+        let ordering = compare_outbound_status(a, b, sort_key);
+        if sort_descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    let alive_count = outbound_statuses.iter().filter(|status| status.alive).count();
+
+    let sort_header = |label: &str, key: ObservatorySortKey| {
+        let onclick = {
+            let update_client_status = props.update_client_status.clone();
+            let ui_status = ui_status.clone();
+            Callback::from(move |_| {
+                update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                    observatory_sort_key: key,
+                    observatory_sort_descending: ui_status.observatory_sort_key == key
+                        && !ui_status.observatory_sort_descending,
+                    ..ui_status.clone()
+                }));
+            })
+        };
+        let indicator = match (sort_key == key, sort_descending) {
+            (true, false) => " \u{25B2}",
+            (true, true) => " \u{25BC}",
+            (false, _) => "",
+        };
+        html! {
+            <th scope="col" role="button" {onclick}>{label}{indicator}</th>
+        }
+    };
+
+    html! {
+        <div class={classes!("my-3")}>
+            <div class={classes!("mb-2")}>
+                <Badge class={"me-1"} style={Color::Success}>{alive_count} {" alive"}</Badge>
+                <Badge class={"me-1"} style={Color::Secondary}>{outbound_statuses.len()} {" probed"}</Badge>
+            </div>
+            <table class={classes!("table", "table-sm", "table-hover", "align-middle")}>
+                <thead>
+                    <tr>
+                        {sort_header("Outbound Tag", ObservatorySortKey::OutboundTag)}
+                        {sort_header("Status", ObservatorySortKey::Alive)}
+                        {sort_header("Latency", ObservatorySortKey::Delay)}
+                        {sort_header("Last Seen", ObservatorySortKey::LastSeen)}
+                        {sort_header("Last Try", ObservatorySortKey::LastTry)}
+                        <th scope="col">{"Last Error"}</th>
+                        <th scope="col">{"Subscription Server"}</th>
+                    </tr>
+                </thead>
+                <tbody>
+                {
+                    for outbound_statuses.iter().map(|status| {
+                        html! {
+                            <tr>
+                                <td><code>{&status.outbound_tag}</code></td>
+                                <td>
+                                {
+                                    match status.alive {
+                                        true => html! { <Badge style={Color::Success}>{"Alive"}</Badge> },
+                                        false => html! { <Badge style={Color::Danger}>{"Dead"}</Badge> },
+                                    }
+                                }
+                                </td>
+                                <td>
+                                {
+                                    match status.alive {
+                                        true => html! { <>{status.delay}{" ms"}</> },
+                                        false => html! { <span class={classes!("text-muted")}>{"-"}</span> },
+                                    }
+                                }
+                                </td>
+                                <td>{format_unix_time_ago(status.last_seen_time)}</td>
+                                <td>{format_unix_time_ago(status.last_try_time)}</td>
+                                <td><small class={classes!("text-break")}>{&status.last_error_reason}</small></td>
+                                <td>
+                                    <SubscriptionServerLink client_status={props.client_status.clone()}
+                                        update_client_status={props.update_client_status.clone()}
+                                        outbound_tag={status.outbound_tag.clone()} />
+                                </td>
+                            </tr>
+                        }
+                    })
+                }
+                </tbody>
+            </table>
+            <DuplicateServersUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        </div>
+    }
+}
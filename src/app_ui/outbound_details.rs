@@ -0,0 +1,104 @@
+use crate::client_status::outbound_config::OutboundConfig;
+use crate::client_status::ui_status::UIStatus;
+use crate::client_status::{ClientStatus, ClientStatusAction};
+use yew::prelude::*;
+use yew::{function_component, Html};
+
+const MASKED_SECRET: &str = "\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}\u{2022}";
+
+fn user_id_label(config: &OutboundConfig) -> &'static str {
+    match config.protocol.as_str() {
+        "vmess" | "vless" => "User ID",
+        _ => "Password",
+    }
+}
+
+fn method_label(config: &OutboundConfig) -> &'static str {
+    match config.protocol.as_str() {
+        "vless" => "Flow",
+        "shadowsocks" => "Cipher",
+        _ => "Security",
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct OutboundConfigDetailsProps {
+    pub client_status: ClientStatus,
+    pub update_client_status: Callback<ClientStatusAction>,
+    pub outbound_tag: String,
+}
+
+#[function_component]
+pub fn OutboundConfigDetailsUI(props: &OutboundConfigDetailsProps) -> Html {
+    let core_link = &props.client_status.core_link;
+    let ui_status = props.client_status.ui_status.clone();
+    let is_revealed = ui_status.server_secrets_revealed.contains(&props.outbound_tag);
+
+    let config = core_link
+        .outbound_configs_by_outbound_tag()
+        .remove(&props.outbound_tag);
+    let Some(config) = config else {
+        let reason = match core_link
+            .subscription_servers_by_outbound_tag()
+            .get(&props.outbound_tag)
+        {
+            Some(server_ref) => core_link.missing_outbound_config_reason(server_ref),
+            None => "Not a subscription server".to_string(),
+        };
+        return html! {
+            <div class={classes!("text-muted")}><small>{"Configuration not available: "}{reason}</small></div>
+        };
+    };
+
+    let on_reveal_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let outbound_tag = props.outbound_tag.clone();
+        Callback::from(move |_| {
+            let mut server_secrets_revealed = ui_status.server_secrets_revealed.clone();
+            if !server_secrets_revealed.remove(&outbound_tag) {
+                server_secrets_revealed.insert(outbound_tag.clone());
+            }
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                server_secrets_revealed,
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    let row = |label: &str, value: &str| {
+        if value.is_empty() {
+            return html! {};
+        }
+        html! {
+            <tr>
+                <th scope="row" class={classes!("text-nowrap", "pe-3")}>{label.to_string()}</th>
+                <td class={classes!("text-break")}><code>{value.to_string()}</code></td>
+            </tr>
+        }
+    };
+
+    html! {
+        <table class={classes!("table", "table-sm", "table-borderless", "mb-0", "mt-2")}>
+            <tbody>
+                {row("Protocol", &config.protocol)}
+                {row("Address", &config.address)}
+                {row("Port", &config.port.to_string())}
+                <tr>
+                    <th scope="row" class={classes!("text-nowrap", "pe-3")}>{user_id_label(&config)}</th>
+                    <td class={classes!("text-break")}>
+                        <code class={classes!("pe-2")}>{if is_revealed { config.user_id.clone() } else { MASKED_SECRET.to_string() }}</code>
+                        <button class={classes!("btn", "btn-link", "btn-sm", "p-0")} type="button" onclick={on_reveal_callback}>
+                            {if is_revealed { "Hide" } else { "Reveal" }}
+                        </button>
+                    </td>
+                </tr>
+                {row(method_label(&config), &config.method)}
+                {row("Transport", &config.transport)}
+                {row("Host", &config.transport_host)}
+                {row(if config.transport == "grpc" { "Service Name" } else { "Path" }, &config.transport_path)}
+                {row("TLS", if config.security.is_empty() { "none" } else { &config.security })}
+                {row("SNI", &config.sni)}
+            </tbody>
+        </table>
+    }
+}
@@ -0,0 +1,28 @@
+use crate::client_status::route::Route;
+use crate::client_status::{ClientStatus, ClientStatusAction};
+use yew::prelude::*;
+use yew::{function_component, Html};
+
+#[derive(Properties, PartialEq)]
+pub struct SubscriptionServerLinkProps {
+    pub client_status: ClientStatus,
+    pub update_client_status: Callback<ClientStatusAction>,
+    pub outbound_tag: String,
+}
+
+#[function_component]
+pub fn SubscriptionServerLink(props: &SubscriptionServerLinkProps) -> Html {
+    let server_index = props
+        .client_status
+        .core_link
+        .subscription_servers_by_outbound_tag();
+    let Some(server_ref) = server_index.get(&props.outbound_tag).cloned() else {
+        return html! { <span class={classes!("text-muted")}>{"-"}</span> };
+    };
+
+    html! {
+        <a href={Route::Server(props.outbound_tag.clone()).to_hash()}>
+            {&server_ref.subscription_name}{" / "}{&server_ref.display_name}
+        </a>
+    }
+}
@@ -0,0 +1,342 @@
+use crate::app_ui::time_format::format_unix_time_ago;
+use crate::app_ui::Props;
+use crate::client_status::policy::{parse_policy, NamedPolicy, PolicyEngineSettings};
+use crate::client_status::preferences::Preferences;
+use crate::client_status::ClientStatusAction;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::{Badge, Button, ButtonGroup};
+use yew_bootstrap::util::Color;
+
+fn unused_policy_name(settings: &PolicyEngineSettings) -> String {
+    let mut index = settings.policies.len() + 1;
+    loop {
+        let name = format!("policy {}", index);
+        if !settings.policies.iter().any(|policy| policy.name == name) {
+            return name;
+        }
+        index += 1;
+    }
+}
+
+#[function_component]
+pub fn PolicyUI(props: &Props) -> Html {
+    let preferences = props.client_status.preferences.clone();
+    let settings = preferences.policy_engine.clone();
+    let active_policy = settings.active().cloned();
+
+    let update_settings = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        move |policy_engine: PolicyEngineSettings| {
+            update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                policy_engine,
+                ..preferences.clone()
+            }));
+        }
+    };
+
+    // The name is edited as a draft and only renamed on blur, so the field can be cleared and
+    // retyped; `None` shows the active policy's name.
+    let name_draft = use_state(|| None::<String>);
+    let name_error = name_draft.as_ref().and_then(|name| {
+        if name.is_empty() {
+            Some("A name is required".to_string())
+        } else if name != &settings.active_policy
+            && settings.policies.iter().any(|policy| &policy.name == name)
+        {
+            Some(format!("Another policy is already named \"{}\"", name))
+        } else {
+            None
+        }
+    });
+
+    let on_enabled_change = {
+        let update_settings = update_settings.clone();
+        let settings = settings.clone();
+        Callback::from(move |event: Event| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(input) = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok()) {
+                update_settings(PolicyEngineSettings {
+                    enabled: input.checked(),
+                    ..settings.clone()
+                });
+            }
+        })
+    };
+
+    let on_dry_run_change = {
+        let update_settings = update_settings.clone();
+        let settings = settings.clone();
+        Callback::from(move |event: Event| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(input) = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok()) {
+                update_settings(PolicyEngineSettings {
+                    dry_run: input.checked(),
+                    ..settings.clone()
+                });
+            }
+        })
+    };
+
+    let on_active_change = {
+        let update_settings = update_settings.clone();
+        let settings = settings.clone();
+        let name_draft = name_draft.clone();
+        Callback::from(move |event: Event| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(select) = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok()) {
+                name_draft.set(None);
+                update_settings(PolicyEngineSettings {
+                    active_policy: select.value(),
+                    ..settings.clone()
+                });
+            }
+        })
+    };
+
+    let on_name_input = {
+        let name_draft = name_draft.clone();
+        Callback::from(move |event: InputEvent| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(input) = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok()) {
+                name_draft.set(Some(input.value()));
+            }
+        })
+    };
+
+    let on_name_change = {
+        let update_settings = update_settings.clone();
+        let settings = settings.clone();
+        let name_draft = name_draft.clone();
+        let name_valid = name_error.is_none();
+        Callback::from(move |_: Event| {
+            let Some(name) = (*name_draft).clone() else {
+                return;
+            };
+            if !name_valid {
+                return;
+            }
+            name_draft.set(None);
+            let mut settings = settings.clone();
+            for policy in settings.policies.iter_mut() {
+                if policy.name == settings.active_policy {
+                    policy.name = name.clone();
+                }
+            }
+            settings.active_policy = name;
+            update_settings(settings);
+        })
+    };
+
+    let on_source_input = {
+        let update_settings = update_settings.clone();
+        let settings = settings.clone();
+        Callback::from(move |event: InputEvent| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(textarea) = target.and_then(|t| t.dyn_into::<HtmlTextAreaElement>().ok()) {
+                let mut settings = settings.clone();
+                for policy in settings.policies.iter_mut() {
+                    if policy.name == settings.active_policy {
+                        policy.source = textarea.value();
+                    }
+                }
+                update_settings(settings);
+            }
+        })
+    };
+
+    let on_add_callback = {
+        let update_settings = update_settings.clone();
+        let settings = settings.clone();
+        let name_draft = name_draft.clone();
+        Callback::from(move |_| {
+            let mut settings = settings.clone();
+            let name = unused_policy_name(&settings);
+            settings.policies.push(NamedPolicy {
+                name: name.clone(),
+                source: "prefer any\n".to_string(),
+            });
+            settings.active_policy = name;
+            name_draft.set(None);
+            update_settings(settings);
+        })
+    };
+
+    let on_remove_callback = {
+        let update_settings = update_settings.clone();
+        let settings = settings.clone();
+        let name_draft = name_draft.clone();
+        Callback::from(move |_| {
+            let mut settings = settings.clone();
+            let active_policy = settings.active_policy.clone();
+            settings.policies.retain(|policy| policy.name != active_policy);
+            settings.active_policy = match settings.policies.first() {
+                Some(policy) => policy.name.clone(),
+                None => "".to_string(),
+            };
+            name_draft.set(None);
+            update_settings(settings);
+        })
+    };
+
+    let policy_log = &props.client_status.core_link.policy_log;
+
+    html! {
+        <div>
+            <Card class="mt-3">
+                <CardHeader>{"Selection Policy"}</CardHeader>
+                <CardBody>
+                    <div class={classes!("form-check", "form-switch", "mb-2")}>
+                        <input class={classes!("form-check-input")} type="checkbox" id="policy-enabled"
+                            checked={settings.enabled} onchange={on_enabled_change} />
+                        <label class={classes!("form-check-label")} for="policy-enabled">
+                            {"Evaluate the active policy on every refresh"}
+                        </label>
+                    </div>
+                    <div class={classes!("form-check", "form-switch", "mb-3")}>
+                        <input class={classes!("form-check-input")} type="checkbox" id="policy-dry-run"
+                            checked={settings.dry_run} onchange={on_dry_run_change} />
+                        <label class={classes!("form-check-label")} for="policy-dry-run">
+                            {"Dry run: show what would be selected without changing the balancer"}
+                        </label>
+                    </div>
+                    <small class={classes!("text-muted", "d-block", "mb-3")}>
+                        {"The policy only changes the override when its pick changes, and leaves overrides set by hand, by a timer, the watchdog or the schedule alone."}
+                    </small>
+                    <div class={classes!("row", "g-2", "mb-3")}>
+                        <div class={classes!("col-md-6")}>
+                            <label class={classes!("form-label")} for="policy-active">{"Active policy"}</label>
+                            <select class={classes!("form-select")} id="policy-active" onchange={on_active_change}>
+                            {
+                                for settings.policies.iter().map(|policy| html! {
+                                    <option value={policy.name.clone()} selected={policy.name == settings.active_policy}>{policy.name.clone()}</option>
+                                })
+                            }
+                            </select>
+                        </div>
+                        <div class={classes!("col-md-6")}>
+                            <label class={classes!("form-label")} for="policy-name">{"Name"}</label>
+                            <input class={classes!("form-control", name_error.as_ref().map(|_| "is-invalid"))} type="text" id="policy-name"
+                                disabled={active_policy.is_none()}
+                                value={(*name_draft).clone().unwrap_or_else(|| settings.active_policy.clone())}
+                                oninput={on_name_input} onchange={on_name_change} />
+                            {
+                                match &name_error {
+                                    Some(error) => html! { <div class={classes!("invalid-feedback")}>{error.clone()}</div> },
+                                    None => html! {},
+                                }
+                            }
+                        </div>
+                    </div>
+                    <ButtonGroup>
+                        <Button outline=true onclick={on_add_callback}>{"Add Policy"}</Button>
+                        <Button outline=true style={Color::Danger} disabled={active_policy.is_none()} onclick={on_remove_callback}>{"Remove Policy"}</Button>
+                    </ButtonGroup>
+                </CardBody>
+            </Card>
+            {
+                match &active_policy {
+                    Some(policy) => html! {
+                        <Card class="mt-3">
+                            <CardHeader>{format!("Rules of {}", policy.name)}</CardHeader>
+                            <CardBody>
+                                <textarea class={classes!("form-control", "font-monospace", "mb-2")} rows="8"
+                                    value={policy.source.clone()} oninput={on_source_input} />
+                                <small class={classes!("text-muted", "d-block", "mb-2")}>
+                                    {"One rule per line: "}
+                                    <code>{"exclude <conditions>"}</code>{" or "}
+                                    <code>{"prefer <conditions> [max 300ms]"}</code>
+                                    {". Conditions are "}
+                                    <code>{"any"}</code>{", "}
+                                    <code>{"name contains \"text\""}</code>{", "}
+                                    <code>{"tag prefix \"text\""}</code>{", "}
+                                    <code>{"subscription \"name\""}</code>{" and "}
+                                    <code>{"metadata key = \"value\""}</code>
+                                    {", joined with "}<code>{"and"}</code>{"."}
+                                </small>
+                                {
+                                    match parse_policy(&policy.source) {
+                                        Ok(rules) => html! {
+                                            <ol class={classes!("mb-0")}>
+                                                { for rules.iter().map(|rule| html! { <li><code>{rule.describe()}</code></li> }) }
+                                            </ol>
+                                        },
+                                        Err(e) => html! {
+                                            <div class={classes!("text-danger")}>{format!("Line {}: {}", e.line, e.message)}</div>
+                                        },
+                                    }
+                                }
+                            </CardBody>
+                        </Card>
+                    },
+                    None => html! {},
+                }
+            }
+            <Card class="mt-3">
+                <CardHeader>{"Latest Decision"}</CardHeader>
+                <CardBody>
+                {
+                    match &policy_log.latest {
+                        Some(entry) => html! {
+                            <div>
+                                <div class={classes!("mb-2")}>
+                                    <small class={classes!("text-muted", "pe-2")}>{format_unix_time_ago(entry.at)}</small>
+                                    {
+                                        match &entry.decision.winner {
+                                            Some(winner) => html! { <>{"Selects "}<code>{winner.clone()}</code></> },
+                                            None => html! { {"No selection"} },
+                                        }
+                                    }
+                                </div>
+                                <ul class={classes!("mb-0")}>
+                                    { for entry.decision.explanation.iter().map(|line| html! { <li>{line.clone()}</li> }) }
+                                </ul>
+                            </div>
+                        },
+                        None => html! { <span class={classes!("text-muted")}>{"The policy has not been evaluated yet"}</span> },
+                    }
+                }
+                </CardBody>
+            </Card>
+            <Card class="mt-3">
+                <CardHeader>{"Decision Log"}</CardHeader>
+                <CardBody>
+                    <table class={classes!("table", "table-sm", "mb-0")}>
+                        <thead>
+                            <tr>
+                                <th scope="col">{"Time"}</th>
+                                <th scope="col">{"Policy"}</th>
+                                <th scope="col">{"Selection"}</th>
+                                <th scope="col"></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                        {
+                            for policy_log.entries.iter().rev().map(|entry| html! {
+                                <tr>
+                                    <td>{format_unix_time_ago(entry.at)}</td>
+                                    <td>{entry.policy_name.clone()}</td>
+                                    <td>{entry.decision.winner.clone().unwrap_or_else(|| "None".to_string())}</td>
+                                    <td>
+                                    {
+                                        if entry.applied {
+                                            html! { <Badge style={Color::Success}>{"Applied"}</Badge> }
+                                        } else {
+                                            html! { <Badge style={Color::Secondary}>{"Not applied"}</Badge> }
+                                        }
+                                    }
+                                    </td>
+                                </tr>
+                            })
+                        }
+                        </tbody>
+                    </table>
+                </CardBody>
+            </Card>
+        </div>
+    }
+}
@@ -0,0 +1,338 @@
+use crate::app_ui::Props;
+use crate::client_status::preferences::{
+    ImportSourceDefaults, LatencyClass, LatencyThresholds, Preferences, Theme, PREFERENCES_STORE,
+    REFRESH_INTERVAL_CHOICES_MS,
+};
+use crate::client_status::storage::VersionedStore;
+use crate::client_status::subscription_changes::SUBSCRIPTION_CHANGES_STORE;
+use crate::client_status::subscription_refresh::SUBSCRIPTION_REFRESH_STORE;
+use crate::client_status::ui_status::{UIStatus, UI_STATUS_STORE};
+use crate::client_status::ClientStatusAction;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::util::Color;
+
+pub fn latency_color(thresholds: &LatencyThresholds, delay_ms: i64) -> Color {
+    match thresholds.classify(delay_ms) {
+        LatencyClass::Fast => Color::Success,
+        LatencyClass::Moderate => Color::Warning,
+        LatencyClass::Slow => Color::Danger,
+    }
+}
+
+/// Sets Bootstrap's `data-bs-theme` on the document, following the system setting for `Auto`.
+pub fn apply_theme(theme: Theme) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let prefers_dark = window
+        .match_media("(prefers-color-scheme: dark)")
+        .ok()
+        .flatten()
+        .is_some_and(|query| query.matches());
+    let resolved = match theme {
+        Theme::Auto if prefers_dark => Theme::Dark,
+        Theme::Auto => Theme::Light,
+        theme => theme,
+    };
+    if let Some(root) = window
+        .document()
+        .and_then(|document| document.document_element())
+    {
+        let _ = root.set_attribute("data-bs-theme", resolved.name());
+    }
+}
+
+fn select_value(event: &Event) -> Option<String> {
+    let target: Option<EventTarget> = event.target();
+    target
+        .and_then(|t| t.dyn_into::<HtmlSelectElement>().ok())
+        .map(|select| select.value())
+}
+
+fn input_value(event: &InputEvent) -> Option<String> {
+    let target: Option<EventTarget> = event.target();
+    target
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .map(|input| input.value())
+}
+
+fn describe_refresh_interval(interval_ms: u32) -> String {
+    match interval_ms {
+        ms if ms % 1000 == 0 => format!("Every {}s", ms / 1000),
+        ms => format!("Every {}ms", ms),
+    }
+}
+
+#[function_component]
+pub fn GeneralPreferencesUI(props: &Props) -> Html {
+    let preferences = props.client_status.preferences.clone();
+
+    let on_refresh_interval_change = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        Callback::from(move |event: Event| {
+            if let Some(refresh_interval_ms) = select_value(&event).and_then(|v| v.parse().ok()) {
+                update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                    refresh_interval_ms,
+                    ..preferences.clone()
+                }));
+            }
+        })
+    };
+
+    let on_theme_change = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        Callback::from(move |event: Event| {
+            if let Some(theme) = select_value(&event).and_then(|v| Theme::from_name(&v)) {
+                update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                    theme,
+                    ..preferences.clone()
+                }));
+            }
+        })
+    };
+
+    let update_thresholds = |update: fn(&mut LatencyThresholds, i64)| {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        Callback::from(move |event: InputEvent| {
+            if let Some(value) = input_value(&event).and_then(|v| v.parse::<i64>().ok()) {
+                let mut latency_thresholds = preferences.latency_thresholds;
+                update(&mut latency_thresholds, value.max(0));
+                update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                    latency_thresholds,
+                    ..preferences.clone()
+                }));
+            }
+        })
+    };
+    let on_fetch_documents_change = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        Callback::from(move |event: Event| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(input) = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok()) {
+                update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                    fetch_documents_automatically: input.checked(),
+                    ..preferences.clone()
+                }));
+            }
+        })
+    };
+
+    let on_fast_input = update_thresholds(|thresholds, value| thresholds.fast_ms = value);
+    let on_slow_input = update_thresholds(|thresholds, value| thresholds.slow_ms = value);
+    let thresholds = preferences.latency_thresholds;
+
+    html! {
+        <Card class="mt-3">
+            <CardHeader>{"General"}</CardHeader>
+            <CardBody>
+                <div class={classes!("row", "g-3", "mb-3")}>
+                    <div class={classes!("col-md-6")}>
+                        <label class={classes!("form-label")} for="preferences-refresh-interval">{"Poll the core"}</label>
+                        <select id="preferences-refresh-interval" class={classes!("form-select")} onchange={on_refresh_interval_change}>
+                        {
+                            for REFRESH_INTERVAL_CHOICES_MS.iter().map(|interval_ms| html! {
+                                <option value={interval_ms.to_string()} selected={preferences.refresh_interval_ms == *interval_ms}>
+                                    {describe_refresh_interval(*interval_ms)}
+                                </option>
+                            })
+                        }
+                        </select>
+                    </div>
+                    <div class={classes!("col-md-6")}>
+                        <label class={classes!("form-label")} for="preferences-theme">{"Theme"}</label>
+                        <select id="preferences-theme" class={classes!("form-select")} onchange={on_theme_change}>
+                        {
+                            for Theme::ALL.iter().map(|theme| html! {
+                                <option value={theme.name()} selected={preferences.theme == *theme}>{theme.name()}</option>
+                            })
+                        }
+                        </select>
+                    </div>
+                </div>
+                <div class={classes!("row", "g-3")}>
+                    <div class={classes!("col-md-6")}>
+                        <label class={classes!("form-label")} for="preferences-latency-fast">{"Fast up to (ms)"}</label>
+                        <input class={classes!("form-control")} type="number" min="0" id="preferences-latency-fast"
+                            value={thresholds.fast_ms.to_string()} oninput={on_fast_input} />
+                    </div>
+                    <div class={classes!("col-md-6")}>
+                        <label class={classes!("form-label")} for="preferences-latency-slow">{"Slow above (ms)"}</label>
+                        <input class={classes!("form-control")} type="number" min="0" id="preferences-latency-slow"
+                            value={thresholds.slow_ms.to_string()} oninput={on_slow_input} />
+                    </div>
+                </div>
+                {
+                    if thresholds.slow_ms < thresholds.fast_ms {
+                        html! { <small class={classes!("text-warning")}>{"The slow threshold is below the fast one, so nothing is shown as moderate."}</small> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <div class={classes!("form-check", "mt-3")}>
+                    <input class={classes!("form-check-input")} type="checkbox" id="preferences-fetch-documents"
+                        checked={preferences.fetch_documents_automatically} onchange={on_fetch_documents_change} />
+                    <label class={classes!("form-check-label")} for="preferences-fetch-documents">
+                        {"Fetch subscription documents in the background"}
+                    </label>
+                    <div class={classes!("form-text")}>
+                        {"Server configurations, duplicates and usage come from the subscription documents. This browser fetches them straight from the subscription URLs, tokens included, rather than through the import outbound, and most providers refuse such cross-origin requests. When off, documents are only fetched with the Fetch Document button."}
+                    </div>
+                </div>
+            </CardBody>
+        </Card>
+    }
+}
+
+#[function_component]
+pub fn ImportDefaultsUI(props: &Props) -> Html {
+    let preferences = props.client_status.preferences.clone();
+    let import_defaults = preferences.import_defaults.clone();
+    let reported_outbound_tags = props.client_status.core_link.reported_outbound_tags();
+
+    let on_import_using_tag_input = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        Callback::from(move |event: InputEvent| {
+            if let Some(import_using_tag) = input_value(&event) {
+                update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                    import_defaults: ImportSourceDefaults {
+                        import_using_tag,
+                        ..preferences.import_defaults.clone()
+                    },
+                    ..preferences.clone()
+                }));
+            }
+        })
+    };
+
+    let on_expire_input = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        Callback::from(move |event: InputEvent| {
+            if let Some(default_expire_seconds) = input_value(&event).and_then(|v| v.parse().ok()) {
+                update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                    import_defaults: ImportSourceDefaults {
+                        default_expire_seconds,
+                        ..preferences.import_defaults.clone()
+                    },
+                    ..preferences.clone()
+                }));
+            }
+        })
+    };
+
+    let on_reset_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                import_defaults: ImportSourceDefaults::default(),
+                ..preferences.clone()
+            }));
+        })
+    };
+
+    html! {
+        <Card class="mt-3">
+            <CardHeader>{"New Subscription Defaults"}</CardHeader>
+            <CardBody>
+                <datalist id="import-defaults-outbound-tags">
+                    { for reported_outbound_tags.iter().map(|tag| html! { <option value={tag.clone()} /> }) }
+                </datalist>
+                <div class={classes!("row", "g-3", "mb-3")}>
+                    <div class={classes!("col-md-6")}>
+                        <label class={classes!("form-label")} for="import-defaults-tag">{"Fetch through outbound"}</label>
+                        <input class={classes!("form-control")} type="text" id="import-defaults-tag"
+                            list="import-defaults-outbound-tags"
+                            value={import_defaults.import_using_tag.clone()} oninput={on_import_using_tag_input} />
+                    </div>
+                    <div class={classes!("col-md-6")}>
+                        <label class={classes!("form-label")} for="import-defaults-expire">{"Default expiry (seconds)"}</label>
+                        <input class={classes!("form-control")} type="number" min="0" id="import-defaults-expire"
+                            value={import_defaults.default_expire_seconds.to_string()} oninput={on_expire_input} />
+                    </div>
+                </div>
+                <small class={classes!("text-muted", "d-block", "mb-2")}>{"Used for subscriptions added from now on; existing ones keep their settings."}</small>
+                <button class={classes!("btn", "btn-outline-secondary", "btn-sm")} type="button" onclick={on_reset_callback}>{"Reset to Defaults"}</button>
+            </CardBody>
+        </Card>
+    }
+}
+
+#[function_component]
+pub fn StoredStateUI(props: &Props) -> Html {
+    let on_reset_layout_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let route = props.client_status.ui_status.route.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                route: route.clone(),
+                ..UIStatus::default()
+            }));
+        })
+    };
+
+    let store_html = |store: &'static VersionedStore| {
+        let on_discard_callback = {
+            let update_client_status = props.update_client_status.clone();
+            let client_status = props.client_status.clone();
+            Callback::from(move |_| {
+                store.discard_unreadable();
+                // Saves the state in use now over the unreadable value.
+                update_client_status.emit(ClientStatusAction::SetPreferences(
+                    client_status.preferences.clone(),
+                ));
+                update_client_status.emit(ClientStatusAction::SetUIStatus(
+                    client_status.ui_status.clone(),
+                ));
+            })
+        };
+        html! {
+            <li>
+                <code>{store.key}</code>{format!(" version {}", store.version())}
+                {
+                    if store.is_unreadable() {
+                        html! {
+                            <div class={classes!("text-warning")}>
+                                {"Could not be loaded, so defaults are in use and changes are not saved. A copy is kept under "}
+                                <code>{store.backup_key()}</code>{". "}
+                                <button class={classes!("btn", "btn-link", "btn-sm", "p-0", "align-baseline")} type="button"
+                                    onclick={on_discard_callback}>{"Save over it"}</button>
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </li>
+        }
+    };
+
+    html! {
+        <Card class="mt-3">
+            <CardHeader>{"Stored State"}</CardHeader>
+            <CardBody>
+                <p class={classes!("mb-2")}>
+                    {"Preferences, the layout of this page and the subscription change history are kept in this browser's local storage."}
+                </p>
+                <ul class={classes!("list-unstyled", "small", "text-muted")}>
+                    {store_html(&PREFERENCES_STORE)}
+                    {store_html(&UI_STATUS_STORE)}
+                    {store_html(&SUBSCRIPTION_CHANGES_STORE)}
+                    {store_html(&SUBSCRIPTION_REFRESH_STORE)}
+                </ul>
+                <button class={classes!("btn", "btn-outline-secondary", "btn-sm")} type="button" onclick={on_reset_layout_callback}>
+                    {"Reset Layout and Drafts"}
+                </button>
+            </CardBody>
+        </Card>
+    }
+}
@@ -0,0 +1,271 @@
+use crate::app_ui::time_format::{format_duration_seconds, format_unix_time_ago};
+use crate::app_ui::Props;
+use crate::client_status::preferences::Preferences;
+use crate::client_status::schedule::{
+    format_minute_of_day, format_minute_of_week, local_minute_of_week, parse_minute_of_day,
+    ScheduleRange, ScheduleSettings, Weekday,
+};
+use crate::client_status::ClientStatusAction;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::{Button, ButtonGroup};
+
+/// Names a scheduled target by its preset label when one exists.
+pub fn describe_scheduled_target(preferences: &Preferences, target: &str) -> String {
+    if target.is_empty() {
+        return "Running".to_string();
+    }
+    match preferences.preset_for_target(target) {
+        Some(preset) => preset.label.clone(),
+        None => target.to_string(),
+    }
+}
+
+pub fn describe_next_scheduled_change(preferences: &Preferences) -> Option<String> {
+    if !preferences.schedule.enabled {
+        return None;
+    }
+    let now = local_minute_of_week();
+    let (minutes_until, target) = preferences.schedule.next_change(now)?;
+    Some(format!(
+        "{} at {} (in {})",
+        describe_scheduled_target(preferences, target.as_deref().unwrap_or("")),
+        format_minute_of_week(now + minutes_until),
+        format_duration_seconds(minutes_until as i64 * 60)
+    ))
+}
+
+#[function_component]
+pub fn ScheduleEditorUI(props: &Props) -> Html {
+    let preferences = props.client_status.preferences.clone();
+    let schedule = preferences.schedule.clone();
+
+    let update_schedule = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        move |schedule: ScheduleSettings| {
+            update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                schedule,
+                ..preferences.clone()
+            }));
+        }
+    };
+
+    let update_range = {
+        let update_schedule = update_schedule.clone();
+        let schedule = schedule.clone();
+        move |index: usize, update: Box<dyn Fn(&mut ScheduleRange)>| {
+            let mut schedule = schedule.clone();
+            if let Some(range) = schedule.ranges.get_mut(index) {
+                update(range);
+            }
+            update_schedule(schedule);
+        }
+    };
+
+    let on_enabled_change = {
+        let update_schedule = update_schedule.clone();
+        let schedule = schedule.clone();
+        Callback::from(move |event: Event| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(input) = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok()) {
+                update_schedule(ScheduleSettings {
+                    enabled: input.checked(),
+                    ..schedule.clone()
+                });
+            }
+        })
+    };
+
+    let on_add_callback = {
+        let update_schedule = update_schedule.clone();
+        let schedule = schedule.clone();
+        Callback::from(move |_| {
+            let mut schedule = schedule.clone();
+            schedule.ranges.push(ScheduleRange {
+                days: Weekday::ALL[..5].to_vec(),
+                start_minute: 9 * 60,
+                end_minute: 18 * 60,
+                target: "".to_string(),
+            });
+            update_schedule(schedule);
+        })
+    };
+
+    let mut target_choices: Vec<(String, String)> = vec![("".to_string(), "Running".to_string())];
+    target_choices.extend(
+        preferences
+            .mode_presets
+            .iter()
+            .filter(|preset| !preset.target.is_empty())
+            .map(|preset| (preset.target.clone(), preset.label.clone())),
+    );
+
+    let schedule_events = &props.client_status.core_link.schedule.events;
+
+    html! {
+        <Card class="mt-3">
+            <CardHeader>{"Scheduled Modes"}</CardHeader>
+            <CardBody>
+                <div class={classes!("form-check", "form-switch", "mb-3")}>
+                    <input class={classes!("form-check-input")} type="checkbox" id="schedule-enabled"
+                        checked={schedule.enabled} onchange={on_enabled_change} />
+                    <label class={classes!("form-check-label")} for="schedule-enabled">
+                        {"Switch the operational mode when a time range begins or ends"}
+                    </label>
+                </div>
+                <table class={classes!("table", "table-sm", "align-middle")}>
+                    <thead>
+                        <tr>
+                            <th scope="col">{"Days"}</th>
+                            <th scope="col">{"From"}</th>
+                            <th scope="col">{"Until"}</th>
+                            <th scope="col">{"Mode"}</th>
+                            <th scope="col"></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                    {
+                        for schedule.ranges.iter().enumerate().map(|(index, range)| {
+                            let on_start_input = {
+                                let update_range = update_range.clone();
+                                Callback::from(move |event: InputEvent| {
+                                    let target: Option<EventTarget> = event.target();
+                                    if let Some(input) = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok()) {
+                                        if let Some(minute) = parse_minute_of_day(&input.value()) {
+                                            update_range(index, Box::new(move |range| range.start_minute = minute));
+                                        }
+                                    }
+                                })
+                            };
+                            let on_end_input = {
+                                let update_range = update_range.clone();
+                                Callback::from(move |event: InputEvent| {
+                                    let target: Option<EventTarget> = event.target();
+                                    if let Some(input) = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok()) {
+                                        if let Some(minute) = parse_minute_of_day(&input.value()) {
+                                            update_range(index, Box::new(move |range| range.end_minute = minute));
+                                        }
+                                    }
+                                })
+                            };
+                            let on_target_change = {
+                                let update_range = update_range.clone();
+                                Callback::from(move |event: Event| {
+                                    let target: Option<EventTarget> = event.target();
+                                    if let Some(select) = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok()) {
+                                        let value = select.value();
+                                        update_range(index, Box::new(move |range| range.target = value.clone()));
+                                    }
+                                })
+                            };
+                            let on_remove_callback = {
+                                let update_schedule = update_schedule.clone();
+                                let schedule = schedule.clone();
+                                Callback::from(move |_| {
+                                    let mut schedule = schedule.clone();
+                                    schedule.ranges.remove(index);
+                                    update_schedule(schedule);
+                                })
+                            };
+                            let mut choices = target_choices.clone();
+                            if !choices.iter().any(|(target, _)| *target == range.target) {
+                                choices.push((range.target.clone(), range.target.clone()));
+                            }
+                            html! {
+                                <tr>
+                                    <td>
+                                        <div class={classes!("btn-group", "btn-group-sm")} role="group">
+                                        {
+                                            for Weekday::ALL.iter().map(|day| {
+                                                let day = *day;
+                                                let is_selected = range.days.contains(&day);
+                                                let on_day_click = {
+                                                    let update_range = update_range.clone();
+                                                    Callback::from(move |_| {
+                                                        update_range(index, Box::new(move |range| {
+                                                            if range.days.contains(&day) {
+                                                                range.days.retain(|selected| *selected != day);
+                                                            } else {
+                                                                range.days.push(day);
+                                                                range.days.sort();
+                                                            }
+                                                        }));
+                                                    })
+                                                };
+                                                html! {
+                                                    <button type="button" onclick={on_day_click}
+                                                        class={classes!("btn", if is_selected { "btn-primary" } else { "btn-outline-primary" })}>
+                                                        {day.short_name()}
+                                                    </button>
+                                                }
+                                            })
+                                        }
+                                        </div>
+                                    </td>
+                                    <td>
+                                        <input class={classes!("form-control", "form-control-sm")} type="time"
+                                            value={format_minute_of_day(range.start_minute)} oninput={on_start_input} />
+                                    </td>
+                                    <td>
+                                        <input class={classes!("form-control", "form-control-sm")} type="time"
+                                            value={format_minute_of_day(range.end_minute)} oninput={on_end_input} />
+                                        {
+                                            if range.end_minute <= range.start_minute {
+                                                html! { <small class={classes!("text-muted")}>{"Next day"}</small> }
+                                            } else {
+                                                html! {}
+                                            }
+                                        }
+                                    </td>
+                                    <td>
+                                        <select class={classes!("form-select", "form-select-sm")} onchange={on_target_change}>
+                                        {
+                                            for choices.iter().map(|(target, label)| html! {
+                                                <option value={target.clone()} selected={*target == range.target}>{label.clone()}</option>
+                                            })
+                                        }
+                                        </select>
+                                    </td>
+                                    <td>
+                                        <button class={classes!("btn", "btn-outline-danger", "btn-sm")} type="button"
+                                            onclick={on_remove_callback}>{"Remove"}</button>
+                                    </td>
+                                </tr>
+                            }
+                        })
+                    }
+                    </tbody>
+                </table>
+                <small class={classes!("text-muted", "d-block", "mb-2")}>
+                    {"Outside every range the balancer selects automatically. Where ranges overlap the upper one wins."}
+                </small>
+                <div class={classes!("mb-2")}>
+                {
+                    match describe_next_scheduled_change(&preferences) {
+                        Some(next_change) => html! { <>{"Next scheduled change: "}<b>{next_change}</b></> },
+                        None if schedule.enabled => html! { {"No scheduled changes"} },
+                        None => html! {},
+                    }
+                }
+                </div>
+                <ButtonGroup>
+                    <Button outline=true onclick={on_add_callback}>{"Add Time Range"}</Button>
+                </ButtonGroup>
+                <ul class={classes!("list-unstyled", "mt-3", "mb-0")}>
+                {
+                    for schedule_events.iter().rev().map(|event| html! {
+                        <li>
+                            <small class={classes!("text-muted", "pe-2")}>{format_unix_time_ago(event.at)}</small>
+                            {format!("Switched to {}", describe_scheduled_target(&preferences, &event.target))}
+                        </li>
+                    })
+                }
+                </ul>
+            </CardBody>
+        </Card>
+    }
+}
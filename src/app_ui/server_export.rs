@@ -0,0 +1,127 @@
+use crate::app_ui::Props;
+use crate::client_status::ui_status::UIStatus;
+use crate::client_status::ClientStatusAction;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use yew::prelude::*;
+use yew::{function_component, Html};
+
+fn share_link_qr_svg(share_link: &str) -> Result<String, String> {
+    let code = QrCode::new(share_link.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(256, 256)
+        .quiet_zone(true)
+        .build())
+}
+
+#[function_component]
+pub fn ServerExportModalUI(props: &Props) -> Html {
+    let copy_status = use_state(|| None::<&'static str>);
+    let outbound_tag = props.client_status.ui_status.server_export_outbound.clone();
+    if outbound_tag.is_empty() {
+        return html! {};
+    }
+
+    let core_link = &props.client_status.core_link;
+    let server_ref = core_link
+        .subscription_servers_by_outbound_tag()
+        .remove(&outbound_tag);
+    let share_link = match core_link
+        .outbound_configs_by_outbound_tag()
+        .remove(&outbound_tag)
+    {
+        Some(config) => config.to_share_link(),
+        None => Err(match &server_ref {
+            Some(server_ref) => core_link.missing_outbound_config_reason(server_ref),
+            None => "Not a subscription server".to_string(),
+        }),
+    };
+    let title = match &server_ref {
+        Some(server_ref) => server_ref.display_name.clone(),
+        None => outbound_tag.clone(),
+    };
+
+    let on_close_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = props.client_status.ui_status.clone();
+        let copy_status = copy_status.clone();
+        Callback::from(move |_| {
+            copy_status.set(None);
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                server_export_outbound: "".to_string(),
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    let on_copy_callback = {
+        let share_link = share_link.clone().unwrap_or_default();
+        let copy_status = copy_status.clone();
+        Callback::from(move |_| {
+            let share_link = share_link.clone();
+            let copy_status = copy_status.clone();
+            spawn_local(async move {
+                let Some(window) = web_sys::window() else {
+                    copy_status.set(Some("Copy failed"));
+                    return;
+                };
+                let promise = window.navigator().clipboard().write_text(&share_link);
+                copy_status.set(Some(match JsFuture::from(promise).await {
+                    Ok(_) => "Copied",
+                    Err(_) => "Copy failed",
+                }));
+            });
+        })
+    };
+
+    html! {
+        <>
+            <div class={classes!("modal", "d-block")} tabindex="-1" role="dialog">
+                <div class={classes!("modal-dialog", "modal-dialog-centered")}>
+                    <div class={classes!("modal-content")}>
+                        <div class={classes!("modal-header")}>
+                            <h5 class={classes!("modal-title", "text-break")}>{"Export "}{title}</h5>
+                            <button type="button" class={classes!("btn-close")} aria-label="Close" onclick={on_close_callback.clone()}></button>
+                        </div>
+                        <div class={classes!("modal-body")}>
+                        {
+                            match &share_link {
+                                Ok(share_link) => html! {
+                                    <>
+                                        <div class={classes!("text-center", "mb-3")}>
+                                        {
+                                            match share_link_qr_svg(share_link) {
+                                                Ok(qr_svg) => Html::from_html_unchecked(AttrValue::from(qr_svg)),
+                                                Err(e) => html! { <div class={classes!("text-danger")}>{"Could not draw a QR code: "}{e}</div> },
+                                            }
+                                        }
+                                        </div>
+                                        <textarea class={classes!("form-control", "font-monospace", "mb-2")} rows="4" readonly=true
+                                            value={share_link.clone()} />
+                                        <small class={classes!("text-warning")}>{"The link contains the server credentials."}</small>
+                                    </>
+                                },
+                                Err(e) => html! { <div class={classes!("text-danger")}>{e.clone()}</div> },
+                            }
+                        }
+                        </div>
+                        <div class={classes!("modal-footer")}>
+                            {
+                                match *copy_status {
+                                    Some(status) => html! { <small class={classes!("text-muted", "me-auto")}>{status}</small> },
+                                    None => html! {},
+                                }
+                            }
+                            <button type="button" class={classes!("btn", "btn-primary")} disabled={share_link.is_err()}
+                                onclick={on_copy_callback}>{"Copy Link"}</button>
+                            <button type="button" class={classes!("btn", "btn-secondary")} onclick={on_close_callback}>{"Close"}</button>
+                        </div>
+                    </div>
+                </div>
+            </div>
+            <div class={classes!("modal-backdrop", "show")}></div>
+        </>
+    }
+}
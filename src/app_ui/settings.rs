@@ -1,74 +1,60 @@
+use crate::app_ui::notifications::NotificationSettingsUI;
+use crate::app_ui::preferences::{GeneralPreferencesUI, ImportDefaultsUI, StoredStateUI};
+use crate::app_ui::schedule::{describe_next_scheduled_change, ScheduleEditorUI};
+use crate::app_ui::watchdog::WatchdogSettingsUI;
 use crate::app_ui::Props;
+use crate::client_status::core_link::CoreLinkAction;
+use crate::client_status::preferences::{default_mode_presets, ModePreset, PresetColor, Preferences};
+use crate::client_status::ClientStatusAction;
+use crate::client_status::ClientStatusAction::{ApplyAction, SyncNow};
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 use yew::{function_component, Html};
-use yew_bootstrap::component::{Button, ButtonGroup};
-use crate::client_status::ClientStatusAction::{ApplyAction, SyncNow};
-use crate::client_status::core_link::CoreLinkAction;
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::{Badge, Button, ButtonGroup};
+use yew_bootstrap::util::Color;
+
+pub fn preset_color(color: PresetColor) -> Color {
+    match color {
+        PresetColor::Primary => Color::Primary,
+        PresetColor::Secondary => Color::Secondary,
+        PresetColor::Success => Color::Success,
+        PresetColor::Info => Color::Info,
+        PresetColor::Warning => Color::Warning,
+        PresetColor::Danger => Color::Danger,
+        PresetColor::Dark => Color::Dark,
+    }
+}
 
 #[function_component]
 pub fn RunningStatusUI(props: &Props) -> Html {
-    let override_target = {
-        let override_target = props
-            .client_status
-            .core_link
-            .fetched_router_status
-            .managed
-            .clone();
-        match override_target {
-            Some(router_balancer_status) => {
-                let current_override = router_balancer_status.clone().r#override.unwrap().target;
-                match current_override.as_str() {
-                    "" => None,
-                    _ => Some(current_override.clone()),
-                }
-            }
-            None => None,
-        }
-    };
+    let override_target = props
+        .client_status
+        .core_link
+        .fetched_router_status
+        .override_target();
+    let mode_presets = props.client_status.preferences.mode_presets.clone();
 
-    let is_direct = {
-        match override_target.clone() {
-            Some(target) => match target.as_str() {
-                "direct" => true,
-                _ => false,
-            },
-            None => false,
-        }
-    };
+    let active_preset = override_target
+        .clone()
+        .and_then(|target| props.client_status.preferences.preset_for_target(&target).cloned());
 
-    let is_blackhole = {
-        match override_target.clone() {
-            Some(target) => match target.as_str() {
-                "deny" => true,
-                _ => false,
-            },
-            None => false,
-        }
-    };
-
-    let is_running = { !(is_direct || is_blackhole) };
+    let is_running = active_preset.is_none();
 
     let onclick_running = {
         let update_client_status = props.update_client_status.clone();
         Callback::from(move |_| {
             let action = CoreLinkAction::SetPrimaryBalancerTarget("".to_string());
             update_client_status.emit(ApplyAction(action));
-        })
-    };
-
-    let onclick_direct = {
-        let update_client_status = props.update_client_status.clone();
-        Callback::from(move |_| {
-            let action = CoreLinkAction::SetPrimaryBalancerTarget("direct".to_string());
-            update_client_status.emit(ApplyAction(action));
             update_client_status.emit(SyncNow());
         })
     };
 
-    let onclick_blackhole = {
+    let onclick_preset = |target: String| {
         let update_client_status = props.update_client_status.clone();
         Callback::from(move |_| {
-            let action = CoreLinkAction::SetPrimaryBalancerTarget("deny".to_string());
+            let action = CoreLinkAction::SetPrimaryBalancerTarget(target.clone());
             update_client_status.emit(ApplyAction(action));
             update_client_status.emit(SyncNow());
         })
@@ -89,32 +75,215 @@ pub fn RunningStatusUI(props: &Props) -> Html {
                                 ""
                             }
                         }>{"Running"}</Button>
-                    <Button
-                        outline=true onclick={onclick_direct} class={
-                            if is_direct {
-                                "active"
-                            } else {
-                                ""
+                    {
+                        for mode_presets.iter().map(|preset| {
+                            let is_active = active_preset.as_ref() == Some(preset);
+                            html_nested! {
+                                <Button outline=true style={preset_color(preset.color)}
+                                    onclick={onclick_preset(preset.target.clone())} class={
+                                        if is_active {
+                                            "active"
+                                        } else {
+                                            ""
+                                        }
+                                    }>{preset.label.clone()}</Button>
                             }
-                            }>{"Direct"}</Button>
-                    <Button outline=true onclick={onclick_blackhole} class={
-                            if is_blackhole {
-                                "active"
-                            } else {
-                                ""
-                            }
-                            }>{"Blackhole"}</Button>
-                    </ButtonGroup>
+                        })
+                    }
+                </ButtonGroup>
+                {
+                    match describe_next_scheduled_change(&props.client_status.preferences) {
+                        Some(next_change) => html! {
+                            <div class={classes!("text-muted", "mt-2")}>{"Next scheduled change: "}{next_change}</div>
+                        },
+                        None => html! {},
+                    }
+                }
             </div>
         </div>
     }
 }
 
+fn input_value(event: &InputEvent) -> Option<String> {
+    let target: Option<EventTarget> = event.target();
+    target
+        .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+        .map(|input| input.value())
+}
+
+#[function_component]
+pub fn ModePresetEditorUI(props: &Props) -> Html {
+    let preferences = props.client_status.preferences.clone();
+    let reported_outbound_tags = props.client_status.core_link.reported_outbound_tags();
+
+    let update_preset = |index: usize, update: fn(&mut ModePreset, String)| {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        move |value: String| {
+            let mut mode_presets = preferences.mode_presets.clone();
+            if let Some(preset) = mode_presets.get_mut(index) {
+                update(preset, value);
+            }
+            update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                mode_presets,
+                ..preferences.clone()
+            }));
+        }
+    };
+
+    let on_add_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        Callback::from(move |_| {
+            let mut mode_presets = preferences.mode_presets.clone();
+            mode_presets.push(ModePreset {
+                label: "New Mode".to_string(),
+                target: "".to_string(),
+                color: PresetColor::Primary,
+            });
+            update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                mode_presets,
+                ..preferences.clone()
+            }));
+        })
+    };
+
+    let on_reset_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                mode_presets: default_mode_presets(),
+                ..preferences.clone()
+            }));
+        })
+    };
+
+    html! {
+        <Card class="mt-3">
+            <CardHeader>{"Operational Mode Presets"}</CardHeader>
+            <CardBody>
+                <datalist id="reported-outbound-tags">
+                    { for reported_outbound_tags.iter().map(|tag| html! { <option value={tag.clone()} /> }) }
+                </datalist>
+                <table class={classes!("table", "table-sm", "align-middle")}>
+                    <thead>
+                        <tr>
+                            <th scope="col">{"Label"}</th>
+                            <th scope="col">{"Target Tag"}</th>
+                            <th scope="col">{"Color"}</th>
+                            <th scope="col"></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                    {
+                        for preferences.mode_presets.iter().enumerate().map(|(index, preset)| {
+                            let on_label_input = {
+                                let update = update_preset(index, |preset, value| preset.label = value);
+                                Callback::from(move |event: InputEvent| {
+                                    if let Some(value) = input_value(&event) {
+                                        update(value);
+                                    }
+                                })
+                            };
+                            let on_target_input = {
+                                let update = update_preset(index, |preset, value| preset.target = value);
+                                Callback::from(move |event: InputEvent| {
+                                    if let Some(value) = input_value(&event) {
+                                        update(value);
+                                    }
+                                })
+                            };
+                            let on_color_change = {
+                                let update = update_preset(index, |preset, value| {
+                                    if let Some(color) = PresetColor::from_name(&value) {
+                                        preset.color = color;
+                                    }
+                                });
+                                Callback::from(move |event: Event| {
+                                    let target: Option<EventTarget> = event.target();
+                                    if let Some(select) = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok()) {
+                                        update(select.value());
+                                    }
+                                })
+                            };
+                            let on_remove_callback = {
+                                let update_client_status = props.update_client_status.clone();
+                                let preferences = preferences.clone();
+                                Callback::from(move |_| {
+                                    let mut mode_presets = preferences.mode_presets.clone();
+                                    mode_presets.remove(index);
+                                    update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                                        mode_presets,
+                                        ..preferences.clone()
+                                    }));
+                                })
+                            };
+                            let target_warning = if preset.target.is_empty() {
+                                Some("A target tag is required; \"Running\" already clears the override")
+                            } else if !reported_outbound_tags.contains(&preset.target) {
+                                Some("Not reported by the observatory or the balancer")
+                            } else {
+                                None
+                            };
+                            html! {
+                                <tr>
+                                    <td>
+                                        <input class={classes!("form-control", "form-control-sm")} type="text"
+                                            value={preset.label.clone()} oninput={on_label_input} />
+                                    </td>
+                                    <td>
+                                        <input class={classes!("form-control", "form-control-sm")} type="text"
+                                            list="reported-outbound-tags"
+                                            value={preset.target.clone()} oninput={on_target_input} />
+                                        {
+                                            match target_warning {
+                                                Some(warning) => html! { <small class={classes!("text-warning")}>{warning}</small> },
+                                                None => html! {},
+                                            }
+                                        }
+                                    </td>
+                                    <td>
+                                        <select class={classes!("form-select", "form-select-sm")} onchange={on_color_change}>
+                                        {
+                                            for PresetColor::ALL.iter().map(|color| html! {
+                                                <option value={color.name()} selected={*color == preset.color}>{color.name()}</option>
+                                            })
+                                        }
+                                        </select>
+                                    </td>
+                                    <td>
+                                        <Badge class={"me-1"} style={preset_color(preset.color)}>{preset.label.clone()}</Badge>
+                                        <button class={classes!("btn", "btn-outline-danger", "btn-sm")} type="button"
+                                            onclick={on_remove_callback}>{"Remove"}</button>
+                                    </td>
+                                </tr>
+                            }
+                        })
+                    }
+                    </tbody>
+                </table>
+                <ButtonGroup>
+                    <Button outline=true onclick={on_add_callback}>{"Add Preset"}</Button>
+                    <Button outline=true style={Color::Secondary} onclick={on_reset_callback}>{"Reset to Defaults"}</Button>
+                </ButtonGroup>
+            </CardBody>
+        </Card>
+    }
+}
+
 #[function_component]
 pub fn SettingsUI(props: &Props) -> Html {
     html! {
         <div>
             <RunningStatusUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+            <ModePresetEditorUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+            <ScheduleEditorUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+            <WatchdogSettingsUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+            <GeneralPreferencesUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+            <NotificationSettingsUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+            <ImportDefaultsUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+            <StoredStateUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
         </div>
     }
 }
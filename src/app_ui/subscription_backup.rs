@@ -0,0 +1,262 @@
+use crate::app_ui::Props;
+use crate::client_status::bulk_operation::BulkOperation;
+use crate::client_status::core_link::CoreLinkAction;
+use crate::client_status::subscription_backup::{BackupDiff, SubscriptionBackup};
+use crate::client_status::ui_status::UIStatus;
+use crate::client_status::ClientStatusAction::{ApplyAction, SyncNow};
+use crate::client_status::{now_unix_seconds, ClientStatusAction};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{EventTarget, File, HtmlInputElement};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+use yew_bootstrap::component::Badge;
+use yew_bootstrap::util::Color;
+
+type LoadedBackup = Option<(String, Result<SubscriptionBackup, String>)>;
+
+fn read_backup_file(file: File, loaded: UseStateHandle<LoadedBackup>) {
+    spawn_local(async move {
+        let result = match JsFuture::from(file.text()).await {
+            Ok(text) => SubscriptionBackup::parse(&text.as_string().unwrap_or_default()),
+            Err(_) => Err("could not read the file".to_string()),
+        };
+        loaded.set(Some((file.name(), result)));
+    });
+}
+
+fn backup_file_name() -> String {
+    let date: String = js_sys::Date::new_0()
+        .to_iso_string()
+        .as_string()
+        .unwrap_or_default()
+        .chars()
+        .take(10)
+        .collect();
+    format!("rendezvous-subscriptions-{}.json", date)
+}
+
+#[function_component]
+pub fn SubscriptionBackupUI(props: &Props) -> Html {
+    let loaded = use_state(|| None::<(String, Result<SubscriptionBackup, String>)>);
+    let include_removals = use_state(|| true);
+    let drag_over = use_state(|| false);
+    let ui_status = props.client_status.ui_status.clone();
+    if !ui_status.subscription_backup_card_open {
+        return html! {};
+    }
+    let fetched_subscription = &props.client_status.core_link.fetched_subscription;
+
+    let backup = SubscriptionBackup::from_fetched(fetched_subscription, now_unix_seconds());
+    let unexported = SubscriptionBackup::unexported_names(fetched_subscription);
+    let download_href = format!(
+        "data:application/json;charset=utf-8,{}",
+        utf8_percent_encode(&backup.to_json(), NON_ALPHANUMERIC)
+    );
+
+    let on_file_change_callback = {
+        let loaded = loaded.clone();
+        Callback::from(move |event: Event| {
+            let target: Option<EventTarget> = event.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(file) = input
+                .and_then(|input| input.files())
+                .and_then(|files| files.get(0))
+            {
+                read_backup_file(file, loaded.clone());
+            }
+        })
+    };
+
+    let on_drag_over_callback = {
+        let drag_over = drag_over.clone();
+        Callback::from(move |event: DragEvent| {
+            event.prevent_default();
+            drag_over.set(true);
+        })
+    };
+
+    let on_drag_leave_callback = {
+        let drag_over = drag_over.clone();
+        Callback::from(move |_: DragEvent| drag_over.set(false))
+    };
+
+    let on_drop_callback = {
+        let loaded = loaded.clone();
+        let drag_over = drag_over.clone();
+        Callback::from(move |event: DragEvent| {
+            event.prevent_default();
+            drag_over.set(false);
+            let file = event
+                .data_transfer()
+                .and_then(|data_transfer| data_transfer.files())
+                .and_then(|files| files.get(0));
+            if let Some(file) = file {
+                read_backup_file(file, loaded.clone());
+            }
+        })
+    };
+
+    let on_close_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        let loaded = loaded.clone();
+        Callback::from(move |_| {
+            loaded.set(None);
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_backup_card_open: false,
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    let on_include_removals_callback = {
+        let include_removals = include_removals.clone();
+        Callback::from(move |_| include_removals.set(!*include_removals))
+    };
+
+    let diff = match &*loaded {
+        Some((_, Ok(backup))) => Some(BackupDiff::new(fetched_subscription, backup)),
+        _ => None,
+    };
+
+    let on_apply_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let diff = diff.clone();
+        let include_removals = *include_removals;
+        let fetched_subscription = fetched_subscription.clone();
+        let on_close_callback = on_close_callback.clone();
+        Callback::from(move |event: MouseEvent| {
+            let Some(diff) = &diff else {
+                return;
+            };
+            for source in &diff.added {
+                update_client_status.emit(ApplyAction(CoreLinkAction::RestoreSubscription(
+                    source.to_import_source(),
+                )));
+            }
+            for (existing, source) in &diff.changed {
+                update_client_status.emit(ApplyAction(CoreLinkAction::ReplaceSubscription(
+                    existing.to_import_source(),
+                    source.to_import_source(),
+                )));
+            }
+            if include_removals && !diff.removed.is_empty() {
+                update_client_status.emit(ClientStatusAction::StartBulkOperation(
+                    BulkOperation::removal(
+                        &fetched_subscription,
+                        &diff.removed.iter().cloned().collect(),
+                        now_unix_seconds(),
+                    ),
+                ));
+            }
+            update_client_status.emit(SyncNow());
+            on_close_callback.emit(event);
+        })
+    };
+
+    html! {
+        <Card class="mb-3">
+            <CardHeader>
+                <div class={classes!("d-flex", "align-items-center")}>
+                    <div class={classes!("w-100")}>{"Backup and Restore"}</div>
+                    <button type="button" class={classes!("btn-close")} aria-label="Close" onclick={on_close_callback}></button>
+                </div>
+            </CardHeader>
+            <CardBody>
+                <div class={classes!("mb-3")}>
+                    <a class={classes!("btn", "btn-outline-primary", (backup.subscriptions.is_empty()).then_some("disabled"))}
+                        href={download_href} download={backup_file_name()}>{"Export"}</a>
+                    <small class={classes!("text-muted", "ms-2")}>
+                        {format!("{} subscriptions, including their tag prefix, import tag and expiry", backup.subscriptions.len())}
+                    </small>
+                    {
+                        if unexported.is_empty() {
+                            html! {}
+                        } else {
+                            html! {
+                                <div class={classes!("text-warning")}>
+                                    <small>
+                                        {format!(
+                                            "{} subscriptions are left out because the core has not reported their source yet: {}",
+                                            unexported.len(),
+                                            unexported.join(", ")
+                                        )}
+                                    </small>
+                                </div>
+                            }
+                        }
+                    }
+                </div>
+                <div class={classes!("border", "border-2", "rounded", "p-3", "mb-3", "text-center", if *drag_over { "border-primary" } else { "border-secondary-subtle" })}
+                    ondragover={on_drag_over_callback} ondragleave={on_drag_leave_callback} ondrop={on_drop_callback}>
+                    <div class={classes!("mb-2")}>{"Drop a backup file here, or choose one"}</div>
+                    <input type="file" accept="application/json,.json" class={classes!("form-control")} onchange={on_file_change_callback} />
+                </div>
+                {
+                    match (&*loaded, &diff) {
+                        (None, _) => html! {},
+                        (Some((file_name, Err(e))), _) => html! {
+                            <div class={classes!("text-danger")}>{format!("Cannot restore {}: {}", file_name, e)}</div>
+                        },
+                        (Some((file_name, Ok(_))), Some(diff)) => html! {
+                            <>
+                                <div class={classes!("mb-2")}>
+                                    {format!("{}: {} unchanged", file_name, diff.unchanged)}
+                                </div>
+                                <ul class={classes!("list-unstyled", "small")}>
+                                {
+                                    for diff.added.iter().map(|source| html! {
+                                        <li><Badge class={"me-2"} style={Color::Success}>{"Add"}</Badge>{source.name.clone()}
+                                            <span class={classes!("text-muted", "ms-2", "text-break")}>{source.url.clone()}</span></li>
+                                    })
+                                }
+                                {
+                                    for diff.changed.iter().map(|(existing, source)| html! {
+                                        <li><Badge class={"me-2"} style={Color::Info}>{"Replace"}</Badge>{source.name.clone()}
+                                            <span class={classes!("text-muted", "ms-2", "text-break")}>
+                                                {if existing.url != source.url { format!("{} \u{2192} {}", existing.url, source.url) } else { "settings differ".to_string() }}
+                                            </span></li>
+                                    })
+                                }
+                                {
+                                    for diff.removed.iter().map(|name| html! {
+                                        <li class={classes!((!*include_removals).then_some("text-decoration-line-through"))}>
+                                            <Badge class={"me-2"} style={Color::Danger}>{"Remove"}</Badge>{name.clone()}</li>
+                                    })
+                                }
+                                {
+                                    for diff.kept.iter().map(|name| html! {
+                                        <li><Badge class={"me-2"} style={Color::Secondary}>{"Keep"}</Badge>{name.clone()}
+                                            <span class={classes!("text-muted", "ms-2")}>{"defined in the core configuration"}</span></li>
+                                    })
+                                }
+                                </ul>
+                                {
+                                    if diff.removed.is_empty() {
+                                        html! {}
+                                    } else {
+                                        html! {
+                                            <div class={classes!("form-check", "mb-2")}>
+                                                <input class={classes!("form-check-input")} type="checkbox" id="backup-include-removals"
+                                                    checked={*include_removals} onchange={on_include_removals_callback} />
+                                                <label class={classes!("form-check-label")} for="backup-include-removals">
+                                                    {"Remove subscriptions that are not in the file"}
+                                                </label>
+                                            </div>
+                                        }
+                                    }
+                                }
+                                <button type="button" class={classes!("btn", "btn-primary")}
+                                    disabled={diff.is_empty()} onclick={on_apply_callback}>{"Apply"}</button>
+                            </>
+                        },
+                        _ => html! {},
+                    }
+                }
+            </CardBody>
+        </Card>
+    }
+}
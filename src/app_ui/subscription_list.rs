@@ -1,13 +1,32 @@
+use crate::app_ui::bulk_operation::{BulkOperationProgressUI, BulkOperationToolbarUI};
+use crate::app_ui::document_preview::SubscriptionDocumentPreviewUI;
+use crate::app_ui::favorites::{FavoriteToggleButton, FavoritesUI};
+use crate::app_ui::manual_outbounds::{ManualOutboundAddUI, ManualOutboundsUI};
+use crate::app_ui::outbound_details::OutboundConfigDetailsUI;
+use crate::app_ui::preferences::latency_color;
+use crate::app_ui::server_export::ServerExportModalUI;
+use crate::app_ui::subscription_backup::SubscriptionBackupUI;
+use crate::app_ui::subscription_refresh::{
+    SubscriptionDocumentUI, SubscriptionRefreshUI, SubscriptionUsageUI,
+};
+use crate::app_ui::subscription_remove::{SubscriptionRemoveModalUI, SubscriptionUndoToastUI};
+use crate::app_ui::time_format::{format_duration_seconds, format_unix_time_ago};
 use crate::app_ui::Props;
 use crate::client_status::core_link::CoreLinkAction;
+use crate::client_status::manual_override::OverrideDuration;
+use crate::client_status::route::Route;
+use crate::client_status::subscription_changes::ServerChangeKind;
+use crate::client_status::subscription_form::{
+    validate_subscription_name, validate_subscription_url,
+};
 use crate::client_status::ui_status::UIStatus;
 use crate::client_status::ClientStatusAction::{ApplyAction, SyncNow};
-use crate::client_status::{ClientStatus, ClientStatusAction};
+use crate::client_status::{now_unix_seconds, ClientStatus, ClientStatusAction};
 use crate::grpc::proto::v2ray::core::app::subscription::{
     SubscriptionServer, TrackedSubscriptionStatus,
 };
 use gloo_console::log;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ops::Deref;
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{EventTarget, HtmlInputElement};
@@ -15,7 +34,7 @@ use yew::prelude::*;
 use yew::{function_component, props, Html};
 use yew_bootstrap::component::card::*;
 use yew_bootstrap::component::form::*;
-use yew_bootstrap::component::{Accordion, AccordionItem, Badge, ListGroup, ListGroupItem};
+use yew_bootstrap::component::{Badge, ListGroup, ListGroupItem};
 use yew_bootstrap::util::Color;
 
 #[derive(Properties, PartialEq)]
@@ -38,21 +57,39 @@ pub fn ProxyServerItemControlButton(props: &ProxyServerItemControlButtonProps) -
         &props.subscription_import_tag, &props.server_info.tag
     );
 
-    let on_manually_select_callback = {
-        let currently_active = props.currently_active;
+    let on_manually_select_callback = |duration: OverrideDuration| {
         let update_client_status = props.update_client_status.clone();
         let our_tag = outbound_tag.clone();
         Callback::from(move |_| {
             log!(<std::string::String as Into<JsValue>>::into(String::from(
                 "button"
             )));
-            if currently_manually_selected {
-                let action = CoreLinkAction::SetPrimaryBalancerTarget("".to_string());
-                update_client_status.emit(ApplyAction(action));
-            } else {
-                let action = CoreLinkAction::SetPrimaryBalancerTarget(our_tag.to_string());
-                update_client_status.emit(ApplyAction(action));
-            }
+            let action = CoreLinkAction::SetPrimaryBalancerTarget(our_tag.to_string());
+            update_client_status.emit(ApplyAction(action));
+            update_client_status.emit(ClientStatusAction::SetOverrideExpiry(
+                duration.expiry_for(&our_tag),
+            ));
+            update_client_status.emit(SyncNow());
+        })
+    };
+
+    let on_export_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = props.client_status.ui_status.clone();
+        let outbound_tag = outbound_tag.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                server_export_outbound: outbound_tag.clone(),
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    let on_automatic_select_callback = {
+        let update_client_status = props.update_client_status.clone();
+        Callback::from(move |_| {
+            let action = CoreLinkAction::SetPrimaryBalancerTarget("".to_string());
+            update_client_status.emit(ApplyAction(action));
             update_client_status.emit(SyncNow());
         })
     };
@@ -74,7 +111,23 @@ pub fn ProxyServerItemControlButton(props: &ProxyServerItemControlButtonProps) -
             }
         }
           <ul class={classes!("dropdown-menu")}>
-                <il> <button class={classes!("dropdown-item")} onclick={on_manually_select_callback} type="button"> {"Manually Select"} </button> </il>
+                {
+                    if currently_manually_selected {
+                        html! {
+                            <li> <button class={classes!("dropdown-item")} onclick={on_automatic_select_callback} type="button"> {"Return to Automatic"} </button> </li>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                <li> <h6 class={classes!("dropdown-header")}> {"Manually Select"} </h6> </li>
+                {
+                    for OverrideDuration::ALL.iter().map(|duration| html! {
+                        <li> <button class={classes!("dropdown-item")} onclick={on_manually_select_callback(*duration)} type="button"> {duration.label()} </button> </li>
+                    })
+                }
+                <li> <hr class={classes!("dropdown-divider")} /> </li>
+                <li> <button class={classes!("dropdown-item")} onclick={on_export_callback} type="button"> {"Export"} </button> </li>
           </ul>
         </div>
     }
@@ -87,6 +140,7 @@ pub struct ProxyServerItemProps {
     pub name: String,
     pub server_info: SubscriptionServer,
     pub subscription_import_tag: String,
+    pub subscription_name: String,
 }
 
 #[function_component]
@@ -159,11 +213,65 @@ pub fn ProxyServerItemUI(props: &ProxyServerItemProps) -> Html {
     let is_override_target = override_target.is_some()
         && override_target.unwrap_or(principle_target.clone()) == outbound_tag
         && is_selected;
+    let is_focused = props.client_status.ui_status.subscription_focused_outbound == outbound_tag;
+    let is_routed = props.client_status.ui_status.route == Route::Server(outbound_tag.clone());
+    let is_details_expanded = is_routed
+        || props
+            .client_status
+            .ui_status
+            .server_details_expanded
+            .contains(&outbound_tag);
+    let on_details_toggle_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = props.client_status.ui_status.clone();
+        let outbound_tag = outbound_tag.clone();
+        let subscription_name = props.subscription_name.clone();
+        Callback::from(move |_| {
+            let mut server_details_expanded = ui_status.server_details_expanded.clone();
+            server_details_expanded.remove(&outbound_tag);
+            if is_details_expanded {
+                if is_routed {
+                    Route::Subscription(subscription_name.clone()).navigate();
+                }
+            } else {
+                server_details_expanded.insert(outbound_tag.clone());
+                Route::Server(outbound_tag.clone()).navigate();
+            }
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                server_details_expanded,
+                ..ui_status.clone()
+            }));
+        })
+    };
+    let is_new = props.client_status.core_link.subscription_changes.is_new(
+        &props.subscription_name,
+        &props.name,
+        now_unix_seconds(),
+    );
+    let override_countdown = match &props.client_status.preferences.override_expiry {
+        Some(override_expiry) if is_override_target && override_expiry.target == outbound_tag => Some(
+            format_duration_seconds(override_expiry.expires_at - now_unix_seconds()),
+        ),
+        _ => None,
+    };
 
     html! {
-        <div>
+        <div class={classes!(is_focused.then_some(vec!["border", "border-warning", "rounded", "p-2"]))}>
             <div class={classes!("d-flex")}>
-                <h5 class={classes!("w-100")}> {&diplay_name} </h5>
+                <FavoriteToggleButton client_status={props.client_status.clone()}
+                    update_client_status={props.update_client_status.clone()}
+                    outbound_tag={outbound_tag.clone()}
+                    display_name={diplay_name.clone()} />
+                <h5 class={classes!("w-100")}>
+                    {&diplay_name}
+                    {
+                        if is_new {
+                            html! { <Badge class={"ms-2"} style={Color::Primary}>{"New"}</Badge> }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </h5>
                 <div class={classes!("flex-shrink-1")}>
                     <ProxyServerItemControlButton client_status={props.client_status.clone()}
                         update_client_status={props.update_client_status.clone()}
@@ -175,6 +283,22 @@ pub fn ProxyServerItemUI(props: &ProxyServerItemProps) -> Html {
             </div>
             <div> <b class={classes!("pe-1")}> {"Outbound Tag"} </b> {&outbound_tag} </div>
             <div> <small> <b class={classes!("pe-1")}> {"Identifier"}  </b> {&props.name} </small> </div>
+            <div>
+                <button class={classes!("btn", "btn-link", "btn-sm", "p-0", "text-reset")} type="button" onclick={on_details_toggle_callback}>
+                    {if is_details_expanded { "Hide Configuration" } else { "Show Configuration" }}
+                </button>
+            </div>
+            {
+                if is_details_expanded {
+                    html! {
+                        <OutboundConfigDetailsUI client_status={props.client_status.clone()}
+                            update_client_status={props.update_client_status.clone()}
+                            outbound_tag={outbound_tag.clone()} />
+                    }
+                } else {
+                    html! {}
+                }
+            }
             {
                 if let Some(observation) = observation_result {
                     match observation.alive {
@@ -187,7 +311,7 @@ pub fn ProxyServerItemUI(props: &ProxyServerItemProps) -> Html {
                             }
                             false => {
                                 html! {
-                                    <Badge class={"me-1"} style={Color::Info}>{observation.delay} {"ms"}</Badge>
+                                    <Badge class={"me-1"} style={latency_color(&props.client_status.preferences.latency_thresholds, observation.delay)}>{observation.delay} {"ms"}</Badge>
                                 }
                             }
                         }
@@ -217,7 +341,17 @@ pub fn ProxyServerItemUI(props: &ProxyServerItemProps) -> Html {
             {
                 if is_override_target {
                     html! {
-                        <Badge class={"me-1"} style={Color::Success}>{"Manually Selected"}</Badge>
+                        <>
+                            <Badge class={"me-1"} style={Color::Success}>{"Manually Selected"}</Badge>
+                            {
+                                match override_countdown {
+                                    Some(countdown) => html! {
+                                        <Badge class={"me-1"} style={Color::Warning}>{"Reverts in "}{countdown}</Badge>
+                                    },
+                                    None => html! {},
+                                }
+                            }
+                        </>
                     }
                 } else {
                     html! {
@@ -245,15 +379,12 @@ pub fn SubscriptionControlButton(props: &SubscriptionControlButtonProps) -> Html
     let on_remove_callback = {
         let to_be_removed = props.name.clone();
         let update_client_status = props.update_client_status.clone();
+        let ui_status = props.client_status.ui_status.clone();
         Callback::from(move |_| {
-            log!(<std::string::String as Into<JsValue>>::into(String::from(
-                "button"
-            )));
-
-            let action = CoreLinkAction::RemoveSubscription(to_be_removed.to_string());
-            update_client_status.emit(ApplyAction(action));
-
-            update_client_status.emit(SyncNow());
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_remove_pending: BTreeSet::from([to_be_removed.clone()]),
+                ..ui_status.clone()
+            }));
         })
     };
 
@@ -291,7 +422,8 @@ pub fn SubscriptionControlButton(props: &SubscriptionControlButtonProps) -> Html
         }
           <ul class={classes!("dropdown-menu")}>
                 <il> <button class={classes!("dropdown-item")} onclick={on_update_callback} type="button"> {"Update"} </button> </il>
-                <il> <button class={classes!("dropdown-item")} onclick={on_remove_callback} enabled={ if(is_api_added){"true"}else{"false"} } type="button"> {"Remove"} </button> </il>
+                <il> <button class={classes!("dropdown-item")} onclick={on_remove_callback} disabled={!is_api_added}
+                    title={(!is_api_added).then_some("Defined in the core configuration")} type="button"> {"Remove"} </button> </il>
           </ul>
         </div>
     }
@@ -321,12 +453,19 @@ pub fn SubscriptionListItemUI(props: &SubscriptionItemProps) -> Html {
                         html! {
                         <div>
                             <div class={classes!("d-flex", "mb-3")}>
-                                <div class={classes!("w-100")} />
+                                <div class={classes!("w-100")}>
+                                    <SubscriptionUsageUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()}
+                                        displayed_subscription_name={props.displayed_subscription_name.clone()} />
+                                </div>
                                 <div class={classes!("flex-shrink-1")}>
                                     <SubscriptionControlButton client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()}
                                         name={props.displayed_subscription_name.clone()} subscription_status={tracked_subscription_status.clone()} />
                                 </div>
                             </div>
+                            <SubscriptionRefreshUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()}
+                                displayed_subscription_name={props.displayed_subscription_name.clone()} />
+                            <SubscriptionDocumentUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()}
+                                displayed_subscription_name={props.displayed_subscription_name.clone()} />
                             <ListGroup>
                                 {
                                     for BTreeMap::from_iter(tracked_subscription_status.servers.iter()).iter().map(|(name, serverinfo)| {
@@ -364,12 +503,15 @@ pub fn SubscriptionListItemUI(props: &SubscriptionItemProps) -> Html {
                                                     server_info={cloned_serverinfo}
                                                     client_status={props.client_status.clone()}
                                                     update_client_status={props.update_client_status.clone()}
-                                                    subscription_import_tag={subscription_import_tag.clone()}/>
+                                                    subscription_import_tag={subscription_import_tag.clone()}
+                                                    subscription_name={props.displayed_subscription_name.clone()}/>
                                             </ListGroupItem>
                                         }
                                     })
                                 }
                             </ListGroup>
+                            <SubscriptionChangeLogUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()}
+                                displayed_subscription_name={props.displayed_subscription_name.clone()} />
                         </div>
                         }
                     } else {
@@ -383,6 +525,53 @@ pub fn SubscriptionListItemUI(props: &SubscriptionItemProps) -> Html {
     }
 }
 
+#[function_component]
+pub fn SubscriptionChangeLogUI(props: &SubscriptionItemProps) -> Html {
+    let changes = props
+        .client_status
+        .core_link
+        .subscription_changes
+        .changes_for(&props.displayed_subscription_name);
+
+    html! {
+        <div class={classes!("mt-3")}>
+            <h6>{"Change Log"}</h6>
+            {
+                if changes.is_empty() {
+                    html! { <small class={classes!("text-muted")}>{"No server changes recorded yet"}</small> }
+                } else {
+                    html! {
+                        <ul class={classes!("list-unstyled", "mb-0")}>
+                        {
+                            for changes.iter().rev().map(|change| html! {
+                                <li>
+                                    <small class={classes!("text-muted", "pe-2")}>{format_unix_time_ago(change.at)}</small>
+                                    <Badge class={"me-1"} style={
+                                        match change.kind {
+                                            ServerChangeKind::Added => Color::Success,
+                                            ServerChangeKind::Removed => Color::Danger,
+                                            ServerChangeKind::Changed => Color::Warning,
+                                        }
+                                    }>{change.kind.label()}</Badge>
+                                    {change.display_name.clone()}
+                                    {
+                                        if change.details.is_empty() {
+                                            html! {}
+                                        } else {
+                                            html! { <small class={classes!("text-muted", "ps-2")}>{change.details.join(", ")}</small> }
+                                        }
+                                    }
+                                </li>
+                            })
+                        }
+                        </ul>
+                    }
+                }
+            }
+        </div>
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct SubscriptionListControlButtonProps {
     pub client_status: ClientStatus,
@@ -411,6 +600,30 @@ pub fn SubscriptionListControlButton(props: &SubscriptionListControlButtonProps)
         })
     };
 
+    let on_add_outbound_from_link_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        Callback::from(move |_| {
+            let ui_status = ui_status.clone();
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                manual_outbound_card_open: !ui_status.manual_outbound_card_open,
+                ..ui_status
+            }));
+        })
+    };
+
+    let on_backup_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        Callback::from(move |_| {
+            let ui_status = ui_status.clone();
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_backup_card_open: !ui_status.subscription_backup_card_open,
+                ..ui_status
+            }));
+        })
+    };
+
     html! {
                 <div class={classes!("dropdown")}>
         {
@@ -429,6 +642,8 @@ pub fn SubscriptionListControlButton(props: &SubscriptionListControlButtonProps)
         }
           <ul class={classes!("dropdown-menu")}>
                 <il> <button class={classes!("dropdown-item")} onclick={on_add_new_subscription_callback} type="button"> {"Add"} </button> </il>
+                <il> <button class={classes!("dropdown-item")} onclick={on_add_outbound_from_link_callback} type="button"> {"Add Outbound from Link"} </button> </il>
+                <il> <button class={classes!("dropdown-item")} onclick={on_backup_callback} type="button"> {"Backup and Restore"} </button> </il>
           </ul>
         </div>
     }
@@ -450,19 +665,28 @@ pub fn SubscriptionAddNewSubscription(props: &Props) -> Html {
     };
     let subscription_name = ui_status.subscription_add_new_name.clone();
     let subscription_url = ui_status.subscription_add_new_url.clone();
+    let name_validation = validate_subscription_name(
+        &subscription_name,
+        &props.client_status.core_link.fetched_subscription.managed,
+    );
+    let url_validation = validate_subscription_url(&subscription_url);
+    let form_valid = name_validation.is_ok() && url_validation.is_ok();
     let on_submit_callback = {
         let update_client_status = props.update_client_status.clone();
+        let import_defaults = props.client_status.preferences.import_defaults.clone();
         let subscription_name = subscription_name.clone();
         let subscription_url = subscription_url.clone();
         let ui_status = ui_status.clone();
         Callback::from(move |_| {
+            if !form_valid {
+                return;
+            }
             log!(<std::string::String as Into<JsValue>>::into(String::from(
                 "button"
             )));
             {
                 let action = CoreLinkAction::AddSubscription(
-                    subscription_name.clone(),
-                    subscription_url.clone(),
+                    import_defaults.import_source(&subscription_name, &subscription_url),
                 );
                 update_client_status.emit(ApplyAction(action));
             }
@@ -471,6 +695,8 @@ pub fn SubscriptionAddNewSubscription(props: &Props) -> Html {
                 subscription_add_new_name: "".to_string(),
                 subscription_add_new_url: "".to_string(),
                 subscription_add_new_card_open: false,
+                subscription_preview_open: false,
+                subscription_preview_document: "".to_string(),
                 ..ui_status.clone()
             }));
             {
@@ -527,6 +753,19 @@ pub fn SubscriptionAddNewSubscription(props: &Props) -> Html {
         })
     };
 
+    // Fields are only marked once something has been typed, so an empty form does not open with errors.
+    let name_feedback = match (subscription_name.is_empty(), &name_validation) {
+        (true, _) => FormControlValidation::None,
+        (false, Ok(())) => FormControlValidation::Valid(None),
+        (false, Err(e)) => FormControlValidation::Invalid(e.clone().into()),
+    };
+    let url_feedback = match (subscription_url.is_empty(), &url_validation) {
+        (true, _) => FormControlValidation::None,
+        (false, Ok(_)) => FormControlValidation::Valid(None),
+        (false, Err(e)) => FormControlValidation::Invalid(e.clone().into()),
+    };
+    let url_warning = url_validation.clone().ok().flatten();
+
     html! {
         <div class={ classes!(display_mode) } >
             <Card>
@@ -539,6 +778,7 @@ pub fn SubscriptionAddNewSubscription(props: &Props) -> Html {
                         label="Name"
                         value={{subscription_name.clone()}}
                         oninput={{on_name_change_callback}}
+                        validation={name_feedback}
                     />
                     <FormControl
                         id="input-text"
@@ -547,8 +787,16 @@ pub fn SubscriptionAddNewSubscription(props: &Props) -> Html {
                         label="URL"
                         value={{subscription_url.clone()}}
                         oninput={{on_url_change_callback}}
+                        validation={url_feedback}
                     />
-                    <button class={classes!("btn", "btn-primary","btn-lg","btn-block")} onclick={{on_submit_callback}}> {{"Submit"}} </button>
+                    {
+                        match url_warning {
+                            Some(warning) => html! { <div class={classes!("text-warning", "small", "mb-3")}>{warning}</div> },
+                            None => html! {},
+                        }
+                    }
+                    <SubscriptionDocumentPreviewUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+                    <button class={classes!("btn", "btn-primary","btn-lg","btn-block")} disabled={!form_valid} onclick={{on_submit_callback}}> {{"Submit"}} </button>
                 </CardBody>
             </Card>
         </div>
@@ -557,6 +805,17 @@ pub fn SubscriptionAddNewSubscription(props: &Props) -> Html {
 
 #[function_component]
 pub fn SubscriptionListUI(props: &Props) -> Html {
+    // A deep link to a subscription or one of its servers keeps that subscription expanded.
+    let routed_subscription = match &props.client_status.ui_status.route {
+        Route::Subscription(name) => Some(name.clone()),
+        Route::Server(outbound_tag) => props
+            .client_status
+            .core_link
+            .subscription_servers_by_outbound_tag()
+            .get(outbound_tag)
+            .map(|server_ref| server_ref.subscription_name.clone()),
+        _ => None,
+    };
     html! {
         <div>
                 <div class={classes!("d-flex", "my-3")}>
@@ -569,20 +828,88 @@ pub fn SubscriptionListUI(props: &Props) -> Html {
         <div class={classes!("d-none")}>{"Subscription List"}</div>
         <SubscriptionAddNewSubscription client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()}
                             />
-        <Accordion>
+        <ManualOutboundAddUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        <SubscriptionBackupUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        <FavoritesUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        <ManualOutboundsUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        <ServerExportModalUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        <SubscriptionRemoveModalUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        <SubscriptionUndoToastUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        <BulkOperationProgressUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        <BulkOperationToolbarUI client_status={props.client_status.clone()} update_client_status={props.update_client_status.clone()} />
+        <div class={classes!("accordion")}>
             {
                 for props.client_status.core_link.fetched_subscription.managed.iter().map(|(name, subscription)| {
-                    html_nested! {
-                        <AccordionItem title={name.clone()}>
-                            <SubscriptionListItemUI
-                                client_status={props.client_status.clone()}
-                                update_client_status={props.update_client_status.clone()}
-                                displayed_subscription_name={name.clone()} />
-                        </AccordionItem>
+                    let is_routed = routed_subscription.as_ref() == Some(name);
+                    let is_expanded = is_routed || props.client_status.ui_status.subscription_expanded.contains(name);
+                    let on_toggle_callback = {
+                        let update_client_status = props.update_client_status.clone();
+                        let ui_status = props.client_status.ui_status.clone();
+                        let name = name.clone();
+                        Callback::from(move |_| {
+                            let mut subscription_expanded = ui_status.subscription_expanded.clone();
+                            subscription_expanded.remove(&name);
+                            if is_expanded {
+                                if is_routed {
+                                    Route::Subscriptions.navigate();
+                                }
+                            } else {
+                                subscription_expanded.insert(name.clone());
+                                Route::Subscription(name.clone()).navigate();
+                            }
+                            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                                subscription_expanded,
+                                ..ui_status.clone()
+                            }));
+                        })
+                    };
+                    let is_selected = props.client_status.ui_status.subscription_selected.contains(name);
+                    let on_select_callback = {
+                        let update_client_status = props.update_client_status.clone();
+                        let ui_status = props.client_status.ui_status.clone();
+                        let name = name.clone();
+                        Callback::from(move |_| {
+                            let mut subscription_selected = ui_status.subscription_selected.clone();
+                            if !subscription_selected.remove(&name) {
+                                subscription_selected.insert(name.clone());
+                            }
+                            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                                subscription_selected,
+                                ..ui_status.clone()
+                            }));
+                        })
+                    };
+                    html! {
+                        <div class={classes!("accordion-item")}>
+                            <h2 class={classes!("accordion-header", "d-flex", "align-items-center")}>
+                                <input class={classes!("form-check-input", "mx-3", "flex-shrink-0")} type="checkbox"
+                                    aria-label={format!("Select {}", name)} checked={is_selected} onchange={on_select_callback} />
+                                <button class={classes!("accordion-button", (!is_expanded).then_some("collapsed"))}
+                                    type="button" aria-expanded={is_expanded.to_string()} onclick={on_toggle_callback}>
+                                    {name.clone()}
+                                </button>
+                            </h2>
+                            <div class={classes!("accordion-collapse", "collapse", is_expanded.then_some("show"))}>
+                                <div class={classes!("accordion-body")}>
+                                {
+                                    if is_expanded {
+                                        html! {
+                                            <SubscriptionListItemUI
+                                                client_status={props.client_status.clone()}
+                                                update_client_status={props.update_client_status.clone()}
+                                                displayed_subscription_name={name.clone()} />
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                                </div>
+                            </div>
+                        </div>
                     }
                 })
             }
-        </Accordion>
+        </div>
         </div>
     }
 }
@@ -0,0 +1,152 @@
+use crate::app_ui::subscription_list::SubscriptionItemProps;
+use crate::app_ui::time_format::{format_duration_seconds, format_unix_time_ago};
+use crate::client_status::preferences::Preferences;
+use crate::client_status::subscription_refresh::{
+    describe_interval_minutes, REFRESH_INTERVAL_CHOICES,
+};
+use crate::client_status::{now_unix_seconds, ClientStatusAction};
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlSelectElement};
+use yew::prelude::*;
+use yew::{function_component, Html};
+
+#[function_component]
+pub fn SubscriptionRefreshUI(props: &SubscriptionItemProps) -> Html {
+    let name = props.displayed_subscription_name.clone();
+    let preferences = &props.client_status.preferences;
+    let settings = &preferences.subscription_refresh;
+    let state = &props.client_status.core_link.subscription_refresh;
+    let interval = settings.intervals.get(&name).copied().unwrap_or(0);
+
+    let on_interval_change_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        let name = name.clone();
+        Callback::from(move |event: Event| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(select) = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok()) {
+                let minutes = select.value().parse::<u32>().ok();
+                update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                    subscription_refresh: preferences
+                        .subscription_refresh
+                        .with_interval(&name, minutes),
+                    ..preferences.clone()
+                }));
+            }
+        })
+    };
+
+    let last_run = state.last_runs.get(&name);
+    let next_run_at = state.next_run_at(settings, &name);
+
+    html! {
+        <div class={classes!("d-flex", "align-items-center", "flex-wrap", "gap-2", "mb-3")}>
+            <label class={classes!("form-label", "mb-0")} for={format!("refresh-interval-{}", name)}>{"Auto-refresh"}</label>
+            <select id={format!("refresh-interval-{}", name)} class={classes!("form-select", "form-select-sm", "w-auto")}
+                onchange={on_interval_change_callback}>
+                <option value="0" selected={interval == 0}>{"Off"}</option>
+                {
+                    for REFRESH_INTERVAL_CHOICES.iter().map(|minutes| html! {
+                        <option value={minutes.to_string()} selected={interval == *minutes}>{describe_interval_minutes(*minutes)}</option>
+                    })
+                }
+            </select>
+            <small class={classes!("text-muted")}>
+                {"Last scheduled update: "}
+                {
+                    match last_run {
+                        None => "Never".to_string(),
+                        Some(run) => match &run.result {
+                            Ok(()) => format_unix_time_ago(run.at),
+                            Err(e) => format!("{} (failed: {})", format_unix_time_ago(run.at), e),
+                        },
+                    }
+                }
+            </small>
+            <small class={classes!("text-muted")}>
+                {"Next: "}
+                {
+                    match (interval, next_run_at) {
+                        (0, _) => "not scheduled".to_string(),
+                        (_, None) => "waiting for the next refresh".to_string(),
+                        (_, Some(at)) if at <= now_unix_seconds() => "due now".to_string(),
+                        (_, Some(at)) => format!("in {}", format_duration_seconds(at - now_unix_seconds())),
+                    }
+                }
+            </small>
+        </div>
+    }
+}
+
+/// The state of the subscription's document, which the browser only fetches when asked to or
+/// when enabled in the settings.
+#[function_component]
+pub fn SubscriptionDocumentUI(props: &SubscriptionItemProps) -> Html {
+    let name = props.displayed_subscription_name.clone();
+    let documents = &props.client_status.core_link.fetched_documents;
+    let fetching = documents.fetching.contains(&name);
+
+    let on_fetch_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let name = name.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::FetchSubscriptionDocuments(vec![
+                name.clone(),
+            ]));
+        })
+    };
+
+    html! {
+        <div class={classes!("d-flex", "flex-wrap", "align-items-center", "gap-3", "mb-3")}>
+            <small class={classes!("text-muted")}>
+                {"Document: "}
+                {
+                    match documents.managed.get(&name) {
+                        _ if fetching => "fetching".to_string(),
+                        None => "not fetched".to_string(),
+                        Some(document) => match &document.result {
+                            Ok(configs) => format!("{} servers, fetched {}", configs.len(), format_unix_time_ago(document.fetched_at)),
+                            Err(e) => format!("{} ({})", e, format_unix_time_ago(document.fetched_at)),
+                        },
+                    }
+                }
+            </small>
+            <button class={classes!("btn", "btn-outline-secondary", "btn-sm")} type="button"
+                disabled={fetching} onclick={on_fetch_callback}
+                title="Fetched by this browser directly from the subscription URL, not through the import outbound">
+                {"Fetch Document"}
+            </button>
+        </div>
+    }
+}
+
+#[function_component]
+pub fn SubscriptionUsageUI(props: &SubscriptionItemProps) -> Html {
+    let Some(usage) = props
+        .client_status
+        .core_link
+        .fetched_documents
+        .managed
+        .get(&props.displayed_subscription_name)
+        .and_then(|document| document.usage.clone())
+    else {
+        return html! {};
+    };
+    html! {
+        <small class={classes!("text-muted", "d-flex", "flex-wrap", "gap-2")}>
+            {
+                match usage.remaining_seconds(now_unix_seconds()) {
+                    Some(remaining) if remaining <= 0 => html! { <span class={classes!("text-danger")}>{"Expired"}</span> },
+                    Some(remaining) => html! { <span>{format!("Expires in {}", format_duration_seconds(remaining))}</span> },
+                    None => html! {},
+                }
+            }
+            {
+                match usage.describe_traffic() {
+                    Some(traffic) => html! { <span>{traffic}</span> },
+                    None => html! {},
+                }
+            }
+        </small>
+    }
+}
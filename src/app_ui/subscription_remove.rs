@@ -0,0 +1,174 @@
+use crate::app_ui::Props;
+use crate::client_status::bulk_operation::{BulkItemState, BulkOperation};
+use crate::client_status::core_link::CoreLinkAction;
+use crate::client_status::ui_status::UIStatus;
+use crate::client_status::ClientStatusAction::{ApplyAction, SyncNow};
+use crate::client_status::{now_unix_seconds, ClientStatusAction};
+use std::collections::BTreeSet;
+use yew::prelude::*;
+use yew::{function_component, Html};
+
+/// How long the "Undo" toast stays up after a removal.
+const UNDO_SECONDS: i64 = 15;
+
+#[function_component]
+pub fn SubscriptionRemoveModalUI(props: &Props) -> Html {
+    let ui_status = props.client_status.ui_status.clone();
+    let names = ui_status.subscription_remove_pending.clone();
+    if names.is_empty() {
+        return html! {};
+    }
+    let fetched_subscription = &props.client_status.core_link.fetched_subscription;
+    let is_running = props
+        .client_status
+        .bulk_operation
+        .as_ref()
+        .is_some_and(|bulk_operation| !bulk_operation.is_finished());
+    // The removal runs as a bulk operation, which reports what the core refused and offers the
+    // undo once it has finished.
+    let removal = BulkOperation::removal(fetched_subscription, &names, now_unix_seconds());
+    let can_remove = !removal.pending_names().is_empty() && !is_running;
+    let title = match names.len() {
+        1 => format!("Remove {}", names.iter().next().unwrap()),
+        count => format!("Remove {} Subscriptions", count),
+    };
+
+    let on_cancel_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_remove_pending: BTreeSet::new(),
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    let on_confirm_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        let removal = removal.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::StartBulkOperation(removal.clone()));
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_remove_pending: BTreeSet::new(),
+                subscription_selected: BTreeSet::new(),
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    html! {
+        <>
+            <div class={classes!("modal", "d-block")} tabindex="-1" role="dialog">
+                <div class={classes!("modal-dialog", "modal-dialog-centered")}>
+                    <div class={classes!("modal-content")}>
+                        <div class={classes!("modal-header")}>
+                            <h5 class={classes!("modal-title", "text-break")}>{title}</h5>
+                            <button type="button" class={classes!("btn-close")} aria-label="Close" onclick={on_cancel_callback.clone()}></button>
+                        </div>
+                        <div class={classes!("modal-body")}>
+                            <p>{"The core stops tracking the listed subscriptions and drops their outbounds."}</p>
+                            <ul class={classes!("list-unstyled", "mb-0")}>
+                            {
+                                for removal.items.iter().map(|(name, state)| html! {
+                                    <li class={classes!("mb-2")}>
+                                        <div class={classes!("text-break")}>{name.clone()}</div>
+                                        {
+                                            match (state, removal.sources.get(name)) {
+                                                (BulkItemState::Skipped(reason), _) => html! {
+                                                    <small class={classes!("text-danger")}>{"Cannot be removed here: "}{reason.clone()}</small>
+                                                },
+                                                (_, Some(source)) => html! {
+                                                    <small class={classes!("text-muted", "text-break")}>{"URL: "}{source.url.clone()}</small>
+                                                },
+                                                (_, None) => html! {
+                                                    <small class={classes!("text-warning")}>{"The import source is unknown, so this removal cannot be undone."}</small>
+                                                },
+                                            }
+                                        }
+                                    </li>
+                                })
+                            }
+                            </ul>
+                            {
+                                if is_running {
+                                    html! { <div class={classes!("text-warning", "mt-2")}>{"Wait for the running bulk operation to finish."}</div> }
+                                } else {
+                                    html! {}
+                                }
+                            }
+                        </div>
+                        <div class={classes!("modal-footer")}>
+                            <button type="button" class={classes!("btn", "btn-secondary")} onclick={on_cancel_callback}>{"Cancel"}</button>
+                            <button type="button" class={classes!("btn", "btn-danger")} disabled={!can_remove}
+                                onclick={on_confirm_callback}>{"Remove"}</button>
+                        </div>
+                    </div>
+                </div>
+            </div>
+            <div class={classes!("modal-backdrop", "show")}></div>
+        </>
+    }
+}
+
+#[function_component]
+pub fn SubscriptionUndoToastUI(props: &Props) -> Html {
+    let ui_status = props.client_status.ui_status.clone();
+    let Some(removed) = ui_status.subscription_removed.clone() else {
+        return html! {};
+    };
+    let remaining = removed.removed_at + UNDO_SECONDS - now_unix_seconds();
+    if remaining <= 0 {
+        return html! {};
+    }
+
+    let on_undo_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        let sources = removed.sources.clone();
+        Callback::from(move |_| {
+            for source in &sources {
+                update_client_status.emit(ApplyAction(CoreLinkAction::RestoreSubscription(
+                    source.to_import_source(),
+                )));
+            }
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_removed: None,
+                ..ui_status.clone()
+            }));
+            update_client_status.emit(SyncNow());
+        })
+    };
+
+    let on_dismiss_callback = {
+        let update_client_status = props.update_client_status.clone();
+        let ui_status = ui_status.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                subscription_removed: None,
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    html! {
+        <div class={classes!("toast-container", "position-fixed", "bottom-0", "end-0", "p-3")}>
+            <div class={classes!("toast", "show")} role="status" aria-live="polite">
+                <div class={classes!("d-flex", "align-items-center")}>
+                    <div class={classes!("toast-body", "w-100")}>
+                        {
+                            match removed.sources.as_slice() {
+                                [source] => format!("Removed {}", source.name),
+                                sources => format!("Removed {} subscriptions", sources.len()),
+                            }
+                        }
+                        <small class={classes!("text-muted", "ms-2")}>{format!("{}s", remaining)}</small>
+                    </div>
+                    <button type="button" class={classes!("btn", "btn-sm", "btn-link")} onclick={on_undo_callback}>{"Undo"}</button>
+                    <button type="button" class={classes!("btn-close", "me-2")} aria-label="Close" onclick={on_dismiss_callback}></button>
+                </div>
+            </div>
+        </div>
+    }
+}
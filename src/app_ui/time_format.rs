@@ -0,0 +1,28 @@
+use crate::client_status::now_unix_seconds;
+use wasm_bindgen::JsValue;
+
+pub fn format_duration_seconds(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    match seconds {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m {}s", s / 60, s % 60),
+        s if s < 86400 => format!("{}h {}m", s / 3600, (s % 3600) / 60),
+        s => format!("{}d {}h", s / 86400, (s % 86400) / 3600),
+    }
+}
+
+pub fn format_unix_time_ago(unix_seconds: i64) -> String {
+    if unix_seconds <= 0 {
+        return "Never".to_string();
+    }
+    format!(
+        "{} ago",
+        format_duration_seconds(now_unix_seconds() - unix_seconds)
+    )
+}
+
+/// The time in the browser's locale and time zone.
+pub fn format_unix_time_local(unix_seconds: i64) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(unix_seconds as f64 * 1000.0));
+    date.to_locale_string("default", &JsValue::UNDEFINED).into()
+}
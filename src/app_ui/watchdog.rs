@@ -0,0 +1,163 @@
+use crate::app_ui::time_format::format_unix_time_ago;
+use crate::app_ui::Props;
+use crate::client_status::preferences::Preferences;
+use crate::client_status::ui_status::UIStatus;
+use crate::client_status::watchdog::WatchdogSettings;
+use crate::client_status::ClientStatusAction;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use yew::{function_component, Html};
+use yew_bootstrap::component::card::*;
+
+#[function_component]
+pub fn WatchdogNoticeUI(props: &Props) -> Html {
+    let ui_status = props.client_status.ui_status.clone();
+    let unacknowledged: Vec<_> = props
+        .client_status
+        .core_link
+        .watchdog
+        .events
+        .iter()
+        .filter(|event| event.at > ui_status.watchdog_acknowledged_at)
+        .cloned()
+        .collect();
+    let Some(latest_event) = unacknowledged.last().cloned() else {
+        return html! {};
+    };
+
+    let on_dismiss_callback = {
+        let update_client_status = props.update_client_status.clone();
+        Callback::from(move |_| {
+            update_client_status.emit(ClientStatusAction::SetUIStatus(UIStatus {
+                watchdog_acknowledged_at: latest_event.at,
+                ..ui_status.clone()
+            }));
+        })
+    };
+
+    html! {
+        <div class={classes!("alert", "alert-warning", "d-flex", "align-items-center", "my-2")} role="alert">
+            <div class={classes!("w-100")}>
+                <b class={classes!("pe-1")}>{"Failover Watchdog"}</b>
+                { for unacknowledged.iter().map(|event| html! { <div>{event.describe()}</div> }) }
+            </div>
+            <button type="button" class={classes!("btn-close")} aria-label="Close" onclick={on_dismiss_callback}></button>
+        </div>
+    }
+}
+
+#[function_component]
+pub fn WatchdogSettingsUI(props: &Props) -> Html {
+    let preferences = props.client_status.preferences.clone();
+    let watchdog = preferences.watchdog.clone();
+
+    let update_watchdog = {
+        let update_client_status = props.update_client_status.clone();
+        let preferences = preferences.clone();
+        move |watchdog: WatchdogSettings| {
+            update_client_status.emit(ClientStatusAction::SetPreferences(Preferences {
+                watchdog,
+                ..preferences.clone()
+            }));
+        }
+    };
+
+    let on_enabled_change = {
+        let update_watchdog = update_watchdog.clone();
+        let watchdog = watchdog.clone();
+        Callback::from(move |event: Event| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(input) = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok()) {
+                update_watchdog(WatchdogSettings {
+                    enabled: input.checked(),
+                    ..watchdog.clone()
+                });
+            }
+        })
+    };
+
+    let on_threshold_input = {
+        let update_watchdog = update_watchdog.clone();
+        let watchdog = watchdog.clone();
+        Callback::from(move |event: InputEvent| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(input) = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok()) {
+                if let Ok(failure_threshold) = input.value().parse::<u32>() {
+                    update_watchdog(WatchdogSettings {
+                        failure_threshold: failure_threshold.max(1),
+                        ..watchdog.clone()
+                    });
+                }
+            }
+        })
+    };
+
+    let on_fallback_input = {
+        let update_watchdog = update_watchdog.clone();
+        let watchdog = watchdog.clone();
+        Callback::from(move |event: InputEvent| {
+            let target: Option<EventTarget> = event.target();
+            if let Some(textarea) = target.and_then(|t| t.dyn_into::<HtmlTextAreaElement>().ok()) {
+                update_watchdog(WatchdogSettings {
+                    fallback_targets: textarea
+                        .value()
+                        .lines()
+                        .map(|line| line.trim().to_string())
+                        .filter(|line| !line.is_empty())
+                        .collect(),
+                    ..watchdog.clone()
+                });
+            }
+        })
+    };
+
+    let watchdog_state = &props.client_status.core_link.watchdog;
+
+    html! {
+        <Card class="mt-3">
+            <CardHeader>{"Failover Watchdog"}</CardHeader>
+            <CardBody>
+                <div class={classes!("form-check", "form-switch", "mb-3")}>
+                    <input class={classes!("form-check-input")} type="checkbox" id="watchdog-enabled"
+                        checked={watchdog.enabled} onchange={on_enabled_change} />
+                    <label class={classes!("form-check-label")} for="watchdog-enabled">
+                        {"Leave a manually selected outbound once it stops responding"}
+                    </label>
+                </div>
+                <div class={classes!("mb-3")}>
+                    <label class={classes!("form-label")} for="watchdog-threshold">{"Consecutive dead polls before failing over"}</label>
+                    <input class={classes!("form-control")} type="number" min="1" id="watchdog-threshold"
+                        value={watchdog.failure_threshold.to_string()} oninput={on_threshold_input} />
+                </div>
+                <div class={classes!("mb-3")}>
+                    <label class={classes!("form-label")} for="watchdog-fallback">{"Fallback outbound tags, one per line"}</label>
+                    <textarea class={classes!("form-control")} id="watchdog-fallback" rows="3"
+                        value={watchdog.fallback_targets.join("\n")} oninput={on_fallback_input} />
+                    <small class={classes!("text-muted")}>
+                        {"The first alive fallback is selected; the override is cleared when none is alive."}
+                    </small>
+                </div>
+                {
+                    if watchdog.enabled && !watchdog_state.watched_target.is_empty() {
+                        html! {
+                            <div class={classes!("mb-2")}>
+                                {"Watching "}<code>{&watchdog_state.watched_target}</code>
+                                {format!(", {} consecutive dead polls", watchdog_state.consecutive_failures)}
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                <ul class={classes!("list-unstyled", "mb-0")}>
+                {
+                    for watchdog_state.events.iter().rev().map(|event| html! {
+                        <li><small class={classes!("text-muted", "pe-2")}>{format_unix_time_ago(event.at)}</small>{event.describe()}</li>
+                    })
+                }
+                </ul>
+            </CardBody>
+        </Card>
+    }
+}
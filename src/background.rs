@@ -1,14 +1,36 @@
-use crate::app_ui::_Props::update_client_status;
-use crate::client_status::{ClientStatus, ClientStatusAction};
+use crate::client_status::alerts::read_metrics;
+use crate::client_status::bulk_operation::{
+    BulkItemState, BulkOperationKind, BULK_OPERATION_CONCURRENCY,
+};
+use crate::client_status::core_link::{
+    add_outbound, fetch_subscription_document, remove_outbound, remove_subscription,
+    update_subscription, CoreLinkAction,
+    DOCUMENT_FETCH_CONCURRENCY,
+};
+use crate::client_status::manual_override::{OverrideOrigin, OverrideRecord};
+use crate::client_status::outbound_config::parse_share_link;
+use crate::client_status::policy::{evaluate_policy, parse_policy, PolicyDecision, PolicyLogEntry};
+use crate::client_status::preferences::ManualOutbound;
+use crate::client_status::schedule::{local_minute_of_week, ScheduleEvent};
+use crate::client_status::subscription_refresh::RefreshRun;
+use crate::client_status::{now_unix_seconds, ClientStatus, ClientStatusAction};
 use crate::grpc::GrpcClient;
-use futures::TryFutureExt;
+use futures::{StreamExt, TryFutureExt};
 use gloo_console::__macro::JsValue;
 use gloo_console::log;
 use gloo_timers::future::TimeoutFuture;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use wasm_bindgen_futures::spawn_local;
 use yew::Callback;
 
+/// Lower bound for `Preferences::refresh_interval_ms`, in case the stored value was edited by hand.
+const MINIMUM_REFRESH_INTERVAL_MS: u32 = 500;
+
+static POLL_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Set when a poll was asked for while one was running, such as by Sync Now.
+static POLL_REQUESTED: AtomicBool = AtomicBool::new(false);
+
 #[derive(Clone, Debug)]
 pub struct BackgroundWorker {
     grpc_url: String,
@@ -30,7 +52,12 @@ impl BackgroundWorker {
         Some(())
     }
 
+    /// Starts a poll, or asks the running one to poll again once it is done.
     pub fn refresh(&self) {
+        if POLL_RUNNING.load(Ordering::SeqCst) {
+            POLL_REQUESTED.store(true, Ordering::SeqCst);
+            return;
+        }
         let grpc_url = self.grpc_url.clone();
         let client_status_copy = self.client_status.clone();
         let update_client_status_copy = self.update_client_status.clone();
@@ -44,10 +71,32 @@ impl BackgroundWorker {
         })
     }
 
+    /// Polls the core, unless a poll is already running. A poll works on a copy of the state and
+    /// publishes it as a whole, so two at once would each undo what the other recorded.
     pub async fn refresh_async(
         grpc_url: String,
         client_status_copy: Option<ClientStatus>,
         update_client_status_copy: Option<Callback<ClientStatusAction>>,
+    ) {
+        if POLL_RUNNING.swap(true, Ordering::SeqCst) {
+            POLL_REQUESTED.store(true, Ordering::SeqCst);
+            return;
+        }
+        BackgroundWorker::poll(grpc_url, client_status_copy, update_client_status_copy).await;
+        POLL_RUNNING.store(false, Ordering::SeqCst);
+        if POLL_REQUESTED.swap(false, Ordering::SeqCst) {
+            // Let the UI apply this poll's result first, so the next poll starts from it.
+            TimeoutFuture::new(0).await;
+            if let Some(worker) = crate::app::get_background_refresh().lock().unwrap().as_ref() {
+                worker.refresh();
+            }
+        }
+    }
+
+    async fn poll(
+        grpc_url: String,
+        client_status_copy: Option<ClientStatus>,
+        update_client_status_copy: Option<Callback<ClientStatusAction>>,
     ) {
         let client = crate::grpc::connect(grpc_url).await;
         let mut client_status = client_status_copy.clone();
@@ -70,9 +119,6 @@ impl BackgroundWorker {
                     .iter()
                     .map(|(name, subscription)| name.clone())
                     .collect();
-                client_status_unwrapped
-                    .core_link
-                    .fetched_subscription.managed.clear();
                 for subscription_name in subscription_names {
                     client_status_unwrapped
                         .core_link
@@ -80,17 +126,77 @@ impl BackgroundWorker {
                         .fetch_subscription_content(client.clone(), subscription_name.clone())
                         .await;
                 }
+                let fetched_subscription = client_status_unwrapped
+                    .core_link
+                    .fetched_subscription
+                    .clone();
                 client_status_unwrapped
+                    .core_link
+                    .subscription_changes
+                    .observe(&fetched_subscription, now_unix_seconds());
+                let router_status_started_at = js_sys::Date::now();
+                let router_status_result = client_status_unwrapped
                     .core_link
                     .fetched_router_status
                     .fetch_router_status(client.clone())
                     .await;
+                client_status_unwrapped.core_link.connection_health.record(
+                    router_status_result,
+                    (js_sys::Date::now() - router_status_started_at) as i64,
+                    now_unix_seconds(),
+                );
+
+                if let Some(update_client_status) = &update_client_status_copy {
+                    BackgroundWorker::request_stale_documents(
+                        &client_status_unwrapped,
+                        update_client_status,
+                    );
+                    BackgroundWorker::expire_manual_override(
+                        client.clone(),
+                        &mut client_status_unwrapped,
+                        update_client_status,
+                    )
+                    .await;
+                    BackgroundWorker::run_watchdog(
+                        client.clone(),
+                        &mut client_status_unwrapped,
+                        update_client_status,
+                    )
+                    .await;
+                    BackgroundWorker::run_schedule(
+                        client.clone(),
+                        &mut client_status_unwrapped,
+                        update_client_status,
+                    )
+                    .await;
+                    BackgroundWorker::run_policy_engine(
+                        client.clone(),
+                        &mut client_status_unwrapped,
+                        update_client_status,
+                    )
+                    .await;
+                    update_client_status.emit(ClientStatusAction::RunScheduledRefresh());
+                }
+                // Observed after the automation above so its overrides are recorded with their
+                // origin in this poll.
+                let core_link = &mut client_status_unwrapped.core_link;
+                let switches = core_link.balancer_timeline.observe(
+                    &core_link.fetched_router_status,
+                    &core_link.fetched_measurement,
+                    client_status_unwrapped.preferences.override_origin.as_ref(),
+                    now_unix_seconds(),
+                );
+                BackgroundWorker::run_alerts(&mut client_status_unwrapped);
 
-                update_client_status_copy.unwrap().emit(
+                let update_client_status = update_client_status_copy.unwrap();
+                update_client_status.emit(
                     crate::client_status::ClientStatusAction::SetCoreLink(
                         client_status_unwrapped.core_link,
                     ),
                 );
+                // Detected by the reducer on the state just set, which owns the notification
+                // state.
+                update_client_status.emit(ClientStatusAction::ShowNotifications(switches));
             }
             None => {
                 return;
@@ -98,6 +204,298 @@ impl BackgroundWorker {
         }
     }
 
+    /// Asks for the documents that went stale to be fetched, when enabled. The fetches run
+    /// outside of the poll, see `fetch_subscription_documents`.
+    fn request_stale_documents(
+        client_status: &ClientStatus,
+        update_client_status: &Callback<ClientStatusAction>,
+    ) {
+        if !client_status.preferences.fetch_documents_automatically {
+            return;
+        }
+        let now = now_unix_seconds();
+        let core_link = &client_status.core_link;
+        let stale: Vec<String> = core_link
+            .subscription_urls()
+            .into_iter()
+            .filter(|(name, url)| {
+                let servers_changed_at = core_link.subscription_changes.last_change_at(name);
+                core_link
+                    .fetched_documents
+                    .needs_refresh(name, url, servers_changed_at, now)
+            })
+            .map(|(name, _)| name)
+            .collect();
+        if !stale.is_empty() {
+            update_client_status.emit(ClientStatusAction::FetchSubscriptionDocuments(stale));
+        }
+    }
+
+    async fn expire_manual_override(
+        client: GrpcClient,
+        client_status: &mut ClientStatus,
+        update_client_status: &Callback<ClientStatusAction>,
+    ) {
+        let Some(override_expiry) = client_status.preferences.override_expiry.clone() else {
+            return;
+        };
+        if now_unix_seconds() < override_expiry.expires_at {
+            return;
+        }
+        if client_status.core_link.fetched_router_status.managed.is_none() {
+            // The core is unreachable, try again on the next poll.
+            return;
+        }
+        let current_override = client_status
+            .core_link
+            .fetched_router_status
+            .override_target();
+        if current_override.as_ref() == Some(&override_expiry.target) {
+            log!(<std::string::String as Into<JsValue>>::into(format!(
+                "manual override of {} expired",
+                override_expiry.target
+            )));
+            client_status
+                .core_link
+                .apply_action(
+                    client.clone(),
+                    CoreLinkAction::SetPrimaryBalancerTarget("".to_string()),
+                )
+                .await;
+            let _ = client_status
+                .core_link
+                .fetched_router_status
+                .fetch_router_status(client.clone())
+                .await;
+            BackgroundWorker::record_override_origin(
+                client_status,
+                update_client_status,
+                "".to_string(),
+                OverrideOrigin::Expiry,
+            );
+        }
+        update_client_status.emit(ClientStatusAction::SetOverrideExpiry(None));
+    }
+
+    async fn run_watchdog(
+        client: GrpcClient,
+        client_status: &mut ClientStatus,
+        update_client_status: &Callback<ClientStatusAction>,
+    ) {
+        let override_target = client_status
+            .core_link
+            .fetched_router_status
+            .override_target();
+        let Some(event) = client_status.core_link.watchdog.observe(
+            &client_status.preferences.watchdog,
+            override_target,
+            &client_status.core_link.fetched_measurement,
+            now_unix_seconds(),
+        ) else {
+            return;
+        };
+        log!(<std::string::String as Into<JsValue>>::into(format!(
+            "watchdog: {}",
+            event.describe()
+        )));
+        let target = event.action.balancer_target();
+        client_status
+            .core_link
+            .apply_action(
+                client.clone(),
+                CoreLinkAction::SetPrimaryBalancerTarget(target.clone()),
+            )
+            .await;
+        let _ = client_status
+            .core_link
+            .fetched_router_status
+            .fetch_router_status(client.clone())
+            .await;
+        BackgroundWorker::record_override_origin(
+            client_status,
+            update_client_status,
+            target,
+            OverrideOrigin::Watchdog,
+        );
+        if client_status.preferences.override_expiry.is_some() {
+            update_client_status.emit(ClientStatusAction::SetOverrideExpiry(None));
+        }
+    }
+
+    async fn run_schedule(
+        client: GrpcClient,
+        client_status: &mut ClientStatus,
+        update_client_status: &Callback<ClientStatusAction>,
+    ) {
+        if client_status.core_link.fetched_router_status.managed.is_none() {
+            // The core is unreachable, keep the last check so the change is applied later.
+            return;
+        }
+        let Some(target) = client_status
+            .core_link
+            .schedule
+            .observe(&client_status.preferences.schedule, local_minute_of_week())
+        else {
+            return;
+        };
+        log!(<std::string::String as Into<JsValue>>::into(format!(
+            "schedule selected {:?}",
+            target
+        )));
+        client_status
+            .core_link
+            .apply_action(
+                client.clone(),
+                CoreLinkAction::SetPrimaryBalancerTarget(target.clone()),
+            )
+            .await;
+        let _ = client_status
+            .core_link
+            .fetched_router_status
+            .fetch_router_status(client.clone())
+            .await;
+        BackgroundWorker::record_override_origin(
+            client_status,
+            update_client_status,
+            target.clone(),
+            OverrideOrigin::Schedule,
+        );
+        client_status.core_link.schedule.record(ScheduleEvent {
+            at: now_unix_seconds(),
+            target,
+        });
+        if client_status.preferences.override_expiry.is_some() {
+            update_client_status.emit(ClientStatusAction::SetOverrideExpiry(None));
+        }
+    }
+
+    /// Notes who set the override in the copy this poll works on and in the app state, so the
+    /// rest of this poll and later polls see it.
+    fn record_override_origin(
+        client_status: &mut ClientStatus,
+        update_client_status: &Callback<ClientStatusAction>,
+        target: String,
+        origin: OverrideOrigin,
+    ) {
+        let record = OverrideRecord { target, origin };
+        client_status.preferences.override_origin = Some(record.clone());
+        update_client_status.emit(ClientStatusAction::SetOverrideOrigin(record));
+    }
+
+    /// Evaluates the active policy and applies its winner when the winner changes, unless the
+    /// current override was set by anything other than the policy itself. See `OverrideOrigin`
+    /// for the order of precedence.
+    async fn run_policy_engine(
+        client: GrpcClient,
+        client_status: &mut ClientStatus,
+        update_client_status: &Callback<ClientStatusAction>,
+    ) {
+        let settings = client_status.preferences.policy_engine.clone();
+        if !settings.enabled {
+            return;
+        }
+        let Some(policy) = settings.active() else {
+            return;
+        };
+        if client_status.core_link.fetched_router_status.managed.is_none() {
+            return;
+        }
+        let mut decision = match parse_policy(&policy.source) {
+            Ok(rules) => evaluate_policy(&rules, &client_status.core_link.policy_candidates()),
+            Err(e) => PolicyDecision {
+                winner: None,
+                explanation: vec![format!("Line {}: {}", e.line, e.message)],
+            },
+        };
+        let current_override = client_status
+            .core_link
+            .fetched_router_status
+            .override_target();
+        let previous_winner = client_status
+            .core_link
+            .policy_log
+            .latest
+            .as_ref()
+            .filter(|entry| entry.policy_name == policy.name)
+            .map(|entry| entry.decision.winner.clone());
+        let winner_changed = previous_winner.as_ref() != Some(&decision.winner);
+        let override_origin = OverrideRecord::origin_of(
+            client_status.preferences.override_origin.as_ref(),
+            current_override.as_ref(),
+        );
+        let paused_by = match (&current_override, override_origin) {
+            (None, _) | (Some(_), Some(OverrideOrigin::Policy)) => None,
+            (Some(_), Some(origin)) => Some(origin.label()),
+            (Some(_), None) => Some("another client"),
+        };
+        let should_apply = !settings.dry_run
+            && decision.winner.is_some()
+            && decision.winner != current_override
+            && winner_changed
+            && paused_by.is_none();
+        if let Some(paused_by) = paused_by.filter(|_| decision.winner != current_override) {
+            decision.explanation.push(format!(
+                "Not applied: the current override was set by {}",
+                paused_by
+            ));
+        }
+        if should_apply {
+            let winner = decision.winner.clone().unwrap_or_default();
+            log!(<std::string::String as Into<JsValue>>::into(format!(
+                "policy {} selected {}",
+                policy.name, winner
+            )));
+            client_status
+                .core_link
+                .apply_action(
+                    client.clone(),
+                    CoreLinkAction::SetPrimaryBalancerTarget(winner.clone()),
+                )
+                .await;
+            let _ = client_status
+                .core_link
+                .fetched_router_status
+                .fetch_router_status(client.clone())
+                .await;
+            BackgroundWorker::record_override_origin(
+                client_status,
+                update_client_status,
+                winner,
+                OverrideOrigin::Policy,
+            );
+        }
+        client_status.core_link.policy_log.record(PolicyLogEntry {
+            at: now_unix_seconds(),
+            policy_name: policy.name.clone(),
+            decision,
+            applied: should_apply,
+        });
+    }
+
+    /// Evaluates the alert rules against this poll.
+    fn run_alerts(client_status: &mut ClientStatus) {
+        let settings = &client_status.preferences.alerts;
+        let readings = read_metrics(settings, &client_status.core_link);
+        client_status
+            .core_link
+            .alerts
+            .evaluate(settings, &readings, now_unix_seconds());
+    }
+
+    /// Sends an action to the UI from a task that has no callback of its own.
+    pub fn report(action: ClientStatusAction) {
+        let update_client_status = {
+            let self_lock = crate::app::get_background_refresh();
+            let self_lock = self_lock.lock().unwrap();
+            self_lock
+                .as_ref()
+                .and_then(|worker| worker.update_client_status.clone())
+        };
+        if let Some(update_client_status) = update_client_status {
+            update_client_status.emit(action);
+        }
+    }
+
     pub async fn apply_action<F>(self_lock: Arc<Mutex<Option<BackgroundWorker>>>, action: F)
     where
         F: FnOnce(GrpcClient) + Send + 'static,
@@ -113,12 +511,209 @@ impl BackgroundWorker {
         }
     }
 
+    /// Updates or removes the named subscriptions, a few at a time, reporting each result back to
+    /// the UI as it arrives.
+    pub async fn run_bulk_operation(
+        self_lock: Arc<Mutex<Option<BackgroundWorker>>>,
+        kind: BulkOperationKind,
+        names: Vec<String>,
+    ) {
+        let data = {
+            let self_lock = self_lock.lock().unwrap();
+            self_lock.as_ref().and_then(|worker| {
+                Some((
+                    worker.grpc_url.clone(),
+                    worker.update_client_status.clone()?,
+                ))
+            })
+        };
+        let Some((grpc_url, update_client_status)) = data else {
+            return;
+        };
+        futures::stream::iter(names)
+            .for_each_concurrent(BULK_OPERATION_CONCURRENCY, |name| {
+                let grpc_url = grpc_url.clone();
+                let update_client_status = update_client_status.clone();
+                async move {
+                    update_client_status.emit(ClientStatusAction::SetBulkItemState(
+                        name.clone(),
+                        BulkItemState::Running,
+                    ));
+                    let client = crate::grpc::connect(grpc_url).await;
+                    let result = match kind {
+                        BulkOperationKind::Update => {
+                            update_subscription(client, name.clone()).await
+                        }
+                        BulkOperationKind::Remove => {
+                            remove_subscription(client, name.clone()).await
+                        }
+                    };
+                    if kind == BulkOperationKind::Update {
+                        update_client_status.emit(ClientStatusAction::RecordSubscriptionUpdate(
+                            name.clone(),
+                            result.clone(),
+                        ));
+                    }
+                    update_client_status.emit(ClientStatusAction::SetBulkItemState(
+                        name,
+                        match result {
+                            Ok(()) => BulkItemState::Succeeded,
+                            Err(e) => BulkItemState::Failed(e),
+                        },
+                    ));
+                }
+            })
+            .await;
+        update_client_status.emit(ClientStatusAction::FinishBulkOperation());
+        update_client_status.emit(ClientStatusAction::SyncNow());
+    }
+
+    /// Installs an outbound from its share link and reports the result back to the UI, which
+    /// only keeps the outbound once the core has accepted it.
+    pub async fn add_manual_outbound(
+        self_lock: Arc<Mutex<Option<BackgroundWorker>>>,
+        manual_outbound: ManualOutbound,
+    ) {
+        let data = {
+            let self_lock = self_lock.lock().unwrap();
+            self_lock.as_ref().and_then(|worker| {
+                Some((
+                    worker.grpc_url.clone(),
+                    worker.update_client_status.clone()?,
+                ))
+            })
+        };
+        let Some((grpc_url, update_client_status)) = data else {
+            return;
+        };
+        let result = match parse_share_link(&manual_outbound.share_link) {
+            Ok(config) => {
+                let client = crate::grpc::connect(grpc_url).await;
+                add_outbound(client, manual_outbound.outbound_tag.clone(), config).await
+            }
+            Err(e) => Err(e),
+        };
+        let succeeded = result.is_ok();
+        update_client_status.emit(ClientStatusAction::FinishManualOutbound(
+            manual_outbound,
+            result,
+        ));
+        if succeeded {
+            update_client_status.emit(ClientStatusAction::SyncNow());
+        }
+    }
+
+    /// Removes a manually added outbound from the core and reports the result back to the UI,
+    /// which only forgets the outbound once the core has removed it.
+    pub async fn remove_manual_outbound(
+        self_lock: Arc<Mutex<Option<BackgroundWorker>>>,
+        outbound_tag: String,
+    ) {
+        let data = {
+            let self_lock = self_lock.lock().unwrap();
+            self_lock.as_ref().and_then(|worker| {
+                Some((
+                    worker.grpc_url.clone(),
+                    worker.update_client_status.clone()?,
+                ))
+            })
+        };
+        let Some((grpc_url, update_client_status)) = data else {
+            return;
+        };
+        let client = crate::grpc::connect(grpc_url).await;
+        let result = remove_outbound(client, outbound_tag.clone()).await;
+        let succeeded = result.is_ok();
+        update_client_status.emit(ClientStatusAction::FinishRemovingManualOutbound(
+            outbound_tag,
+            result,
+        ));
+        if succeeded {
+            update_client_status.emit(ClientStatusAction::SyncNow());
+        }
+    }
+
+    /// Updates a subscription picked by the refresh schedule. The reducer claimed it before
+    /// this started, and the result is reported back to it.
+    pub async fn run_scheduled_refresh(
+        self_lock: Arc<Mutex<Option<BackgroundWorker>>>,
+        name: String,
+        started_at: i64,
+    ) {
+        let data = {
+            let self_lock = self_lock.lock().unwrap();
+            self_lock.as_ref().and_then(|worker| {
+                Some((
+                    worker.grpc_url.clone(),
+                    worker.update_client_status.clone()?,
+                ))
+            })
+        };
+        let Some((grpc_url, update_client_status)) = data else {
+            return;
+        };
+        log!(<std::string::String as Into<JsValue>>::into(format!(
+            "scheduled update of {}",
+            name
+        )));
+        let client = crate::grpc::connect(grpc_url).await;
+        let result = update_subscription(client, name.clone()).await;
+        update_client_status.emit(ClientStatusAction::RecordSubscriptionUpdate(
+            name.clone(),
+            result.clone(),
+        ));
+        update_client_status.emit(ClientStatusAction::RecordScheduledRefresh(
+            name,
+            RefreshRun {
+                at: started_at,
+                result,
+            },
+        ));
+    }
+
+    /// Fetches subscription documents from the browser, a few at a time, reporting each one back
+    /// to the UI as it arrives. The names were marked as being fetched by the reducer.
+    pub async fn fetch_subscription_documents(
+        self_lock: Arc<Mutex<Option<BackgroundWorker>>>,
+        sources: Vec<(String, String)>,
+    ) {
+        let update_client_status = {
+            let self_lock = self_lock.lock().unwrap();
+            self_lock
+                .as_ref()
+                .and_then(|worker| worker.update_client_status.clone())
+        };
+        let Some(update_client_status) = update_client_status else {
+            return;
+        };
+        futures::stream::iter(sources)
+            .for_each_concurrent(DOCUMENT_FETCH_CONCURRENCY, |(name, url)| {
+                let update_client_status = update_client_status.clone();
+                async move {
+                    let document =
+                        fetch_subscription_document(&name, url, now_unix_seconds()).await;
+                    update_client_status
+                        .emit(ClientStatusAction::SetSubscriptionDocument(name, document));
+                }
+            })
+            .await;
+    }
+
     pub fn self_refresh(self_lock: Arc<Mutex<Option<BackgroundWorker>>>) {
         let self_copy = self_lock.clone();
         spawn_local(async move {
             loop {
                 // Start the background worker
-                let _ = TimeoutFuture::new(1000).await;
+                let refresh_interval_ms = {
+                    let self_lock = self_copy.lock().unwrap();
+                    self_lock
+                        .as_ref()
+                        .and_then(|worker| worker.client_status.as_ref())
+                        .map(|client_status| client_status.preferences.refresh_interval_ms)
+                        .unwrap_or(1000)
+                        .max(MINIMUM_REFRESH_INTERVAL_MS)
+                };
+                let _ = TimeoutFuture::new(refresh_interval_ms).await;
                 let data = {
                     let self_lock = self_copy.lock().unwrap();
                     match self_lock.as_ref() {
@@ -0,0 +1,455 @@
+use crate::client_status::core_link::CoreLink;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const RESOLVED_ALERT_LIMIT: usize = 100;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum AlertMetric {
+    /// Observatory delay of the selected outbound in milliseconds. A dead outbound reads as
+    /// above any threshold.
+    SelectedLatency,
+    /// Alive servers in the named subscription, or in all subscriptions when the name is empty.
+    AliveServers(String),
+    /// Failed polls of the webcommander in a row.
+    FailedPolls,
+}
+
+impl AlertMetric {
+    pub const KIND_NAMES: [&'static str; 3] = ["Selected latency", "Alive servers", "Failed polls"];
+
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            AlertMetric::SelectedLatency => "Selected latency",
+            AlertMetric::AliveServers(_) => "Alive servers",
+            AlertMetric::FailedPolls => "Failed polls",
+        }
+    }
+
+    /// Builds the metric of the named kind, keeping `subscription` for kinds that take one.
+    pub fn from_kind_name(name: &str, subscription: String) -> Option<AlertMetric> {
+        match name {
+            "Selected latency" => Some(AlertMetric::SelectedLatency),
+            "Alive servers" => Some(AlertMetric::AliveServers(subscription)),
+            "Failed polls" => Some(AlertMetric::FailedPolls),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            AlertMetric::SelectedLatency => "selected latency (ms)".to_string(),
+            AlertMetric::AliveServers(subscription) if subscription.is_empty() => {
+                "alive servers".to_string()
+            }
+            AlertMetric::AliveServers(subscription) => format!("alive servers in {}", subscription),
+            AlertMetric::FailedPolls => "failed polls".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum AlertComparison {
+    Above,
+    Below,
+}
+
+impl AlertComparison {
+    pub const ALL: [AlertComparison; 2] = [AlertComparison::Above, AlertComparison::Below];
+
+    pub fn from_symbol(symbol: &str) -> Option<AlertComparison> {
+        AlertComparison::ALL
+            .iter()
+            .find(|comparison| comparison.symbol() == symbol)
+            .copied()
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            AlertComparison::Above => ">",
+            AlertComparison::Below => "<",
+        }
+    }
+}
+
+/// Fires once the metric has been past `threshold` for `for_seconds`, and resolves once it has
+/// been back past the threshold by at least `clear_margin` for as long, so a value hovering
+/// around the threshold does not flap.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct AlertRule {
+    pub id: u64,
+    pub name: String,
+    pub enabled: bool,
+    pub metric: AlertMetric,
+    pub comparison: AlertComparison,
+    pub threshold: i64,
+    pub for_seconds: i64,
+    pub clear_margin: i64,
+}
+
+impl Default for AlertRule {
+    fn default() -> Self {
+        AlertRule {
+            id: 0,
+            name: "Slow selected outbound".to_string(),
+            enabled: true,
+            metric: AlertMetric::SelectedLatency,
+            comparison: AlertComparison::Above,
+            threshold: 500,
+            for_seconds: 120,
+            clear_margin: 50,
+        }
+    }
+}
+
+impl AlertRule {
+    pub fn describe(&self) -> String {
+        format!(
+            "{} {} {} for {}s",
+            self.metric.describe(),
+            self.comparison.symbol(),
+            self.threshold,
+            self.for_seconds
+        )
+    }
+
+    /// Whether both rules fire on the same readings, whatever their names.
+    fn same_condition(&self, other: &AlertRule) -> bool {
+        self.metric == other.metric
+            && self.comparison == other.comparison
+            && self.threshold == other.threshold
+            && self.for_seconds == other.for_seconds
+            && self.clear_margin == other.clear_margin
+    }
+
+    fn breaches(&self, value: i64) -> bool {
+        match self.comparison {
+            AlertComparison::Above => value > self.threshold,
+            AlertComparison::Below => value < self.threshold,
+        }
+    }
+
+    fn clears(&self, value: i64) -> bool {
+        let margin = self.clear_margin.max(0);
+        match self.comparison {
+            AlertComparison::Above => value <= self.threshold.saturating_sub(margin),
+            AlertComparison::Below => value >= self.threshold.saturating_add(margin),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+#[serde(default)]
+pub struct AlertSettings {
+    pub rules: Vec<AlertRule>,
+}
+
+impl AlertSettings {
+    pub fn next_rule_id(&self) -> u64 {
+        self.rules.iter().map(|rule| rule.id).max().unwrap_or(0) + 1
+    }
+}
+
+/// A metric value read from one poll, with the text shown for it.
+#[derive(PartialEq, Debug, Clone)]
+pub struct AlertReading {
+    pub value: i64,
+    pub label: String,
+}
+
+impl AlertReading {
+    fn number(value: i64) -> AlertReading {
+        AlertReading {
+            value,
+            label: value.to_string(),
+        }
+    }
+}
+
+/// Reads the metric from the latest poll, or `None` when there is nothing to judge by, such as
+/// before the first probe of the selected outbound.
+pub fn read_metric(metric: &AlertMetric, core_link: &CoreLink) -> Option<AlertReading> {
+    let measurement = &core_link.fetched_measurement;
+    match metric {
+        AlertMetric::SelectedLatency => {
+            let target = core_link.fetched_router_status.selected_target()?;
+            let status = measurement.managed.get(&target)?;
+            match status.alive {
+                true => Some(AlertReading::number(status.delay)),
+                false => Some(AlertReading {
+                    value: i64::MAX,
+                    label: "dead".to_string(),
+                }),
+            }
+        }
+        AlertMetric::AliveServers(subscription) => {
+            if !subscription.is_empty()
+                && !core_link
+                    .fetched_subscription
+                    .managed
+                    .contains_key(subscription)
+            {
+                return None;
+            }
+            let alive = core_link
+                .subscription_servers_by_outbound_tag()
+                .iter()
+                .filter(|(_, server_ref)| {
+                    subscription.is_empty() || &server_ref.subscription_name == subscription
+                })
+                .filter(|(tag, _)| {
+                    measurement
+                        .managed
+                        .get(*tag)
+                        .is_some_and(|status| status.alive)
+                })
+                .count();
+            Some(AlertReading::number(alive as i64))
+        }
+        AlertMetric::FailedPolls => Some(AlertReading::number(
+            core_link.connection_health.consecutive_failures as i64,
+        )),
+    }
+}
+
+/// Reads the metric of every enabled rule, keyed by rule id.
+pub fn read_metrics(
+    settings: &AlertSettings,
+    core_link: &CoreLink,
+) -> BTreeMap<u64, Option<AlertReading>> {
+    settings
+        .rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .map(|rule| (rule.id, read_metric(&rule.metric, core_link)))
+        .collect()
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Alert {
+    pub rule_id: u64,
+    pub rule_name: String,
+    pub condition: String,
+    pub started_at: i64,
+    pub resolved_at: Option<i64>,
+    pub last_reading: String,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct RuleProgress {
+    /// When the reading started to breach (while inactive) or to clear (while active).
+    since: Option<i64>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct AlertState {
+    /// Each enabled rule as it was last evaluated, to notice edits.
+    evaluated: BTreeMap<u64, AlertRule>,
+    progress: BTreeMap<u64, RuleProgress>,
+    pub active: BTreeMap<u64, Alert>,
+    pub resolved: Vec<Alert>,
+}
+
+impl AlertState {
+    pub fn new() -> AlertState {
+        AlertState {
+            evaluated: BTreeMap::new(),
+            progress: BTreeMap::new(),
+            active: BTreeMap::new(),
+            resolved: vec![],
+        }
+    }
+
+    /// Applies one poll worth of readings. Alerts of removed or disabled rules are resolved, and
+    /// so are alerts of rules whose condition was edited, which start over. A poll without a
+    /// reading breaks the time a condition has held.
+    pub fn evaluate(
+        &mut self,
+        settings: &AlertSettings,
+        readings: &BTreeMap<u64, Option<AlertReading>>,
+        now: i64,
+    ) {
+        let enabled: BTreeMap<u64, &AlertRule> = settings
+            .rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .map(|rule| (rule.id, rule))
+            .collect();
+        let edited: Vec<u64> = enabled
+            .iter()
+            .filter(|(id, rule)| {
+                self.evaluated
+                    .get(id)
+                    .is_some_and(|evaluated| !evaluated.same_condition(rule))
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        self.evaluated = enabled
+            .iter()
+            .map(|(id, rule)| (*id, (*rule).clone()))
+            .collect();
+        self.progress
+            .retain(|id, _| enabled.contains_key(id) && !edited.contains(id));
+        let orphaned: Vec<u64> = self
+            .active
+            .keys()
+            .filter(|id| !enabled.contains_key(id) || edited.contains(id))
+            .copied()
+            .collect();
+        for id in orphaned {
+            self.resolve(id, now);
+        }
+
+        for (id, rule) in enabled {
+            let Some(Some(reading)) = readings.get(&id) else {
+                self.progress.remove(&id);
+                continue;
+            };
+            let is_active = self.active.contains_key(&id);
+            if let Some(alert) = self.active.get_mut(&id) {
+                alert.rule_name = rule.name.clone();
+                alert.last_reading = reading.label.clone();
+            }
+            let moving = match is_active {
+                false => rule.breaches(reading.value),
+                true => rule.clears(reading.value),
+            };
+            let progress = self
+                .progress
+                .entry(id)
+                .or_insert(RuleProgress { since: None });
+            if !moving {
+                progress.since = None;
+                continue;
+            }
+            let since = *progress.since.get_or_insert(now);
+            if now - since < rule.for_seconds.max(0) {
+                continue;
+            }
+            progress.since = None;
+            if is_active {
+                self.resolve(id, now);
+            } else {
+                self.active.insert(
+                    id,
+                    Alert {
+                        rule_id: id,
+                        rule_name: rule.name.clone(),
+                        condition: rule.describe(),
+                        started_at: since,
+                        resolved_at: None,
+                        last_reading: reading.label.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// When the rule's reading started moving towards firing or resolving, if it has.
+    pub fn pending_since(&self, id: u64) -> Option<i64> {
+        self.progress.get(&id).and_then(|progress| progress.since)
+    }
+
+    fn resolve(&mut self, id: u64, now: i64) {
+        if let Some(mut alert) = self.active.remove(&id) {
+            alert.resolved_at = Some(now);
+            self.resolved.push(alert);
+            if self.resolved.len() > RESOLVED_ALERT_LIMIT {
+                self.resolved.remove(0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> AlertSettings {
+        AlertSettings {
+            rules: vec![AlertRule {
+                id: 1,
+                ..AlertRule::default()
+            }],
+        }
+    }
+
+    fn readings(value: Option<i64>) -> BTreeMap<u64, Option<AlertReading>> {
+        BTreeMap::from([(1, value.map(AlertReading::number))])
+    }
+
+    /// Feeds one reading per entry, ten seconds apart, starting at 0.
+    fn run(state: &mut AlertState, settings: &AlertSettings, values: &[Option<i64>]) {
+        for (index, value) in values.iter().enumerate() {
+            state.evaluate(settings, &readings(*value), index as i64 * 10);
+        }
+    }
+
+    #[test]
+    fn fires_after_the_condition_holds_for_the_duration() {
+        let mut state = AlertState::new();
+        run(&mut state, &settings(), &[Some(600); 12]);
+        assert!(!state.active.contains_key(&1));
+        state.evaluate(&settings(), &readings(Some(600)), 120);
+        assert_eq!(state.active[&1].started_at, 0);
+        assert_eq!(state.active[&1].last_reading, "600");
+    }
+
+    #[test]
+    fn a_missing_reading_restarts_the_wait() {
+        let mut state = AlertState::new();
+        let mut values = vec![Some(600); 8];
+        values.push(None);
+        values.extend([Some(600); 8]);
+        run(&mut state, &settings(), &values);
+        assert!(state.active.is_empty());
+        assert_eq!(state.pending_since(1), Some(90));
+    }
+
+    #[test]
+    fn resolves_only_past_the_clear_margin() {
+        let mut state = AlertState::new();
+        run(&mut state, &settings(), &[Some(600); 13]);
+        assert!(state.active.contains_key(&1));
+        // 480 is under the threshold but within the 50ms margin, so the alert stays.
+        for at in 13..30 {
+            state.evaluate(&settings(), &readings(Some(480)), at * 10);
+        }
+        assert!(state.active.contains_key(&1));
+        for at in 30..43 {
+            state.evaluate(&settings(), &readings(Some(400)), at * 10);
+        }
+        assert!(state.active.is_empty());
+        assert_eq!(state.resolved.len(), 1);
+        assert_eq!(state.resolved[0].resolved_at, Some(420));
+    }
+
+    #[test]
+    fn editing_a_rule_resolves_its_alert_and_starts_over() {
+        let mut state = AlertState::new();
+        run(&mut state, &settings(), &[Some(600); 13]);
+        let mut renamed = settings();
+        renamed.rules[0].name = "Renamed".to_string();
+        state.evaluate(&renamed, &readings(Some(600)), 130);
+        assert_eq!(state.active[&1].rule_name, "Renamed");
+
+        let mut edited = renamed.clone();
+        edited.rules[0].threshold = 700;
+        state.evaluate(&edited, &readings(Some(600)), 140);
+        assert!(state.active.is_empty());
+        assert_eq!(state.resolved.len(), 1);
+        assert_eq!(state.pending_since(1), None);
+    }
+
+    #[test]
+    fn disabling_a_rule_resolves_its_alert() {
+        let mut state = AlertState::new();
+        run(&mut state, &settings(), &[Some(600); 13]);
+        let mut disabled = settings();
+        disabled.rules[0].enabled = false;
+        state.evaluate(&disabled, &readings(Some(600)), 130);
+        assert!(state.active.is_empty());
+        assert_eq!(state.resolved[0].resolved_at, Some(130));
+    }
+}
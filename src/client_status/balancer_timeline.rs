@@ -0,0 +1,146 @@
+use crate::client_status::core_link::{FetchedMeasurement, FetchedRouterStatus};
+use crate::client_status::manual_override::{OverrideOrigin, OverrideRecord};
+
+const BALANCER_TIMELINE_LIMIT: usize = 200;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum BalancerSwitchKind {
+    /// The balancer strategy picked a different principle target.
+    Automatic,
+    /// The override was set, changed or cleared by the given origin, or from outside this
+    /// dashboard when `None`.
+    Override(Option<OverrideOrigin>),
+}
+
+impl BalancerSwitchKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BalancerSwitchKind::Automatic => "Automatic",
+            BalancerSwitchKind::Override(Some(origin)) => origin.label(),
+            BalancerSwitchKind::Override(None) => "Other client",
+        }
+    }
+
+    /// Whether the switch was made without anyone asking for it: by the balancer strategy or by
+    /// automation of this dashboard.
+    pub fn is_automatic(&self) -> bool {
+        match self {
+            BalancerSwitchKind::Automatic => true,
+            BalancerSwitchKind::Override(origin) => {
+                origin.is_some_and(|origin| origin != OverrideOrigin::User)
+            }
+        }
+    }
+}
+
+/// One end of a switch. The latency is the observatory delay when the switch was seen, `None`
+/// when the outbound was dead or not probed.
+#[derive(PartialEq, Debug, Clone)]
+pub struct BalancerSwitchTarget {
+    pub tag: Option<String>,
+    pub latency: Option<i64>,
+}
+
+impl BalancerSwitchTarget {
+    fn observe(tag: Option<String>, measurement: &FetchedMeasurement) -> BalancerSwitchTarget {
+        let latency = tag
+            .as_ref()
+            .and_then(|tag| measurement.managed.get(tag))
+            .filter(|status| status.alive)
+            .map(|status| status.delay);
+        BalancerSwitchTarget { tag, latency }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct BalancerSwitchEvent {
+    pub at: i64,
+    pub kind: BalancerSwitchKind,
+    pub previous: BalancerSwitchTarget,
+    pub new: BalancerSwitchTarget,
+    /// An automatic switch made while an override was in place, so traffic did not move.
+    pub overridden: bool,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct BalancerSnapshot {
+    principle_target: Option<String>,
+    override_target: Option<String>,
+}
+
+impl BalancerSnapshot {
+    fn selected_target(&self) -> Option<String> {
+        self.override_target
+            .clone()
+            .or_else(|| self.principle_target.clone())
+    }
+}
+
+/// Compares successive `BalancerMsg` snapshots and records changes of the principle target and
+/// the override.
+#[derive(PartialEq, Debug, Clone)]
+pub struct BalancerTimeline {
+    last_snapshot: Option<BalancerSnapshot>,
+    pub events: Vec<BalancerSwitchEvent>,
+}
+
+impl BalancerTimeline {
+    pub fn new() -> BalancerTimeline {
+        BalancerTimeline {
+            last_snapshot: None,
+            events: vec![],
+        }
+    }
+
+    /// Feeds one poll into the timeline, returning the switches it recorded. A poll can change
+    /// both the override and the principle target, which are recorded as two switches. Polls where
+    /// the balancer could not be queried are skipped rather than treated as a change.
+    ///
+    /// `override_record` is the last override change made from this dashboard, which tells who
+    /// changed the override.
+    pub fn observe(
+        &mut self,
+        router_status: &FetchedRouterStatus,
+        measurement: &FetchedMeasurement,
+        override_record: Option<&OverrideRecord>,
+        now: i64,
+    ) -> Vec<BalancerSwitchEvent> {
+        if router_status.managed.is_none() {
+            return vec![];
+        }
+        let snapshot = BalancerSnapshot {
+            principle_target: router_status.principle_targets().first().cloned(),
+            override_target: router_status.override_target(),
+        };
+        let Some(previous) = self.last_snapshot.replace(snapshot.clone()) else {
+            return vec![];
+        };
+        let mut events = vec![];
+        if previous.override_target != snapshot.override_target {
+            let origin =
+                OverrideRecord::origin_of(override_record, snapshot.override_target.as_ref());
+            events.push(BalancerSwitchEvent {
+                at: now,
+                kind: BalancerSwitchKind::Override(origin),
+                previous: BalancerSwitchTarget::observe(previous.selected_target(), measurement),
+                new: BalancerSwitchTarget::observe(snapshot.selected_target(), measurement),
+                overridden: false,
+            });
+        }
+        if previous.principle_target != snapshot.principle_target {
+            events.push(BalancerSwitchEvent {
+                at: now,
+                kind: BalancerSwitchKind::Automatic,
+                previous: BalancerSwitchTarget::observe(previous.principle_target, measurement),
+                new: BalancerSwitchTarget::observe(snapshot.principle_target.clone(), measurement),
+                overridden: snapshot.override_target.is_some(),
+            });
+        }
+        self.events.extend(events.iter().cloned());
+        if self.events.len() > BALANCER_TIMELINE_LIMIT {
+            let excess = self.events.len() - BALANCER_TIMELINE_LIMIT;
+            self.events.drain(..excess);
+        }
+        events
+    }
+}
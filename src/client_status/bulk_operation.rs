@@ -0,0 +1,142 @@
+use crate::client_status::core_link::FetchedSubscription;
+use crate::client_status::subscription_backup::BackupImportSource;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How many subscriptions a bulk operation works on at the same time.
+pub const BULK_OPERATION_CONCURRENCY: usize = 3;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum BulkOperationKind {
+    Update,
+    Remove,
+}
+
+impl BulkOperationKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BulkOperationKind::Update => "Update",
+            BulkOperationKind::Remove => "Remove",
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum BulkItemState {
+    Pending,
+    Running,
+    Succeeded,
+    Failed(String),
+    Skipped(String),
+}
+
+/// Progress of a bulk update or removal, keyed by subscription name.
+#[derive(PartialEq, Debug, Clone)]
+pub struct BulkOperation {
+    pub kind: BulkOperationKind,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub items: BTreeMap<String, BulkItemState>,
+    /// Import sources of the subscriptions being removed, so removals the core accepted can be
+    /// undone.
+    pub sources: BTreeMap<String, BackupImportSource>,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct BulkOperationSummary {
+    pub pending: usize,
+    pub running: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+impl BulkOperationSummary {
+    pub fn done(&self) -> usize {
+        self.succeeded + self.failed + self.skipped
+    }
+
+    pub fn total(&self) -> usize {
+        self.done() + self.pending + self.running
+    }
+}
+
+impl BulkOperation {
+    pub fn new(
+        kind: BulkOperationKind,
+        items: BTreeMap<String, BulkItemState>,
+        now: i64,
+    ) -> BulkOperation {
+        BulkOperation {
+            kind,
+            started_at: now,
+            finished_at: None,
+            items,
+            sources: BTreeMap::new(),
+        }
+    }
+
+    /// Removes the named subscriptions. Those defined in the core configuration are skipped.
+    pub fn removal(
+        fetched_subscription: &FetchedSubscription,
+        names: &BTreeSet<String>,
+        now: i64,
+    ) -> BulkOperation {
+        let mut items = BTreeMap::new();
+        let mut sources = BTreeMap::new();
+        for name in names {
+            let status = fetched_subscription.managed.get(name).cloned().flatten();
+            let state = match &status {
+                Some(status) if status.added_by_api => BulkItemState::Pending,
+                Some(_) => BulkItemState::Skipped("defined in the core configuration".to_string()),
+                None => BulkItemState::Skipped("status not loaded".to_string()),
+            };
+            if let Some(source) = status
+                .as_ref()
+                .and_then(|status| status.import_source.as_ref())
+            {
+                sources.insert(name.clone(), BackupImportSource::from_import_source(source));
+            }
+            items.insert(name.clone(), state);
+        }
+        BulkOperation {
+            sources,
+            ..BulkOperation::new(BulkOperationKind::Remove, items, now)
+        }
+    }
+
+    /// Import sources of the subscriptions the core removed.
+    pub fn removed_sources(&self) -> Vec<BackupImportSource> {
+        self.items
+            .iter()
+            .filter(|(_, state)| **state == BulkItemState::Succeeded)
+            .filter_map(|(name, _)| self.sources.get(name).cloned())
+            .collect()
+    }
+
+    /// Subscriptions the operation still has to work on, in name order.
+    pub fn pending_names(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .filter(|(_, state)| **state == BulkItemState::Pending)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    pub fn summary(&self) -> BulkOperationSummary {
+        let mut summary = BulkOperationSummary::default();
+        for state in self.items.values() {
+            match state {
+                BulkItemState::Pending => summary.pending += 1,
+                BulkItemState::Running => summary.running += 1,
+                BulkItemState::Succeeded => summary.succeeded += 1,
+                BulkItemState::Failed(_) => summary.failed += 1,
+                BulkItemState::Skipped(_) => summary.skipped += 1,
+            }
+        }
+        summary
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+}
@@ -0,0 +1,41 @@
+/// Whether the webcommander answered the balancer query on each poll.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ConnectionHealth {
+    pub last_success_at: Option<i64>,
+    pub last_failure_at: Option<i64>,
+    pub last_error: String,
+    pub consecutive_failures: u32,
+    /// How long the last successful balancer query took, in milliseconds.
+    pub round_trip_ms: i64,
+}
+
+impl ConnectionHealth {
+    pub fn new() -> ConnectionHealth {
+        ConnectionHealth {
+            last_success_at: None,
+            last_failure_at: None,
+            last_error: "".to_string(),
+            consecutive_failures: 0,
+            round_trip_ms: 0,
+        }
+    }
+
+    pub fn record(&mut self, result: Result<(), String>, round_trip_ms: i64, now: i64) {
+        match result {
+            Ok(()) => {
+                self.last_success_at = Some(now);
+                self.consecutive_failures = 0;
+                self.round_trip_ms = round_trip_ms;
+            }
+            Err(e) => {
+                self.last_failure_at = Some(now);
+                self.last_error = e;
+                self.consecutive_failures += 1;
+            }
+        }
+    }
+
+    pub fn is_reachable(&self) -> bool {
+        self.last_success_at.is_some() && self.consecutive_failures == 0
+    }
+}
@@ -1,9 +1,24 @@
 use crate::grpc::proto::v2ray::core::app::observatory;
+use crate::grpc::proto::v2ray::core::app::proxyman;
 use crate::grpc::proto::v2ray::core::app::router;
 use crate::grpc::proto::v2ray::core::app::subscription;
+use crate::client_status::alerts::AlertState;
+use crate::client_status::balancer_timeline::BalancerTimeline;
+use crate::client_status::connection_health::ConnectionHealth;
+use crate::client_status::notifications::NotificationState;
+use crate::client_status::outbound_config::{parse_subscription_document, OutboundConfig};
+use crate::client_status::outbound_handler::build_outbound_handler_config;
+use crate::client_status::policy::{PolicyCandidate, PolicyLog};
+use crate::client_status::schedule::ScheduleState;
+use crate::client_status::subscription_changes::SubscriptionChangeTracker;
+use crate::client_status::subscription_refresh::SubscriptionRefreshState;
+use crate::client_status::subscription_usage::{parse_subscription_userinfo, SubscriptionUsage};
+use crate::client_status::watchdog::WatchdogState;
 use crate::grpc::GrpcClient;
+use futures::future::{select, Either};
 use gloo_console::log;
-use std::collections::BTreeMap;
+use gloo_timers::future::TimeoutFuture;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ops::{Deref, DerefMut};
 use std::option::Option;
 use std::sync::MutexGuard;
@@ -59,13 +74,18 @@ impl FetchedMeasurement {
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct FetchedSubscription {
+    /// A status stays `None` until it has been fetched. A failed fetch keeps the previous status.
     pub managed: BTreeMap<String, Option<subscription::TrackedSubscriptionStatus>>,
+    /// Whether the core has listed its subscriptions yet; until then `managed` says nothing
+    /// about which subscriptions exist.
+    pub listed: bool,
 }
 
 impl FetchedSubscription {
     pub fn new() -> FetchedSubscription {
         FetchedSubscription {
             managed: BTreeMap::new(),
+            listed: false,
         }
     }
 
@@ -84,10 +104,12 @@ impl FetchedSubscription {
                     Ok(response) => {
                         let data = response.into_inner();
 
+                        self.managed.retain(|name, _| data.names.contains(name));
                         data.names.iter().for_each(|subscription| {
                             println!("Subscription: {:?}", subscription);
-                            self.managed.insert(subscription.clone(), None);
+                            self.managed.entry(subscription.clone()).or_insert(None);
                         });
+                        self.listed = true;
                     }
                     Err(e) => {
                         println!("Error: {:?}", e);
@@ -138,7 +160,7 @@ impl FetchedRouterStatus {
         FetchedRouterStatus { managed: None }
     }
 
-    pub async fn fetch_router_status(&mut self, grpc_client: GrpcClient) {
+    pub async fn fetch_router_status(&mut self, grpc_client: GrpcClient) -> Result<(), String> {
         println!("Fetching router status");
         let grpc_client_copy = grpc_client.client.clone();
         {
@@ -159,11 +181,13 @@ impl FetchedRouterStatus {
                         self.managed = match data.balancer {
                             Some(balancer) => Some(balancer),
                             None => None,
-                        }
+                        };
+                        Ok(())
                     }
                     Err(e) => {
                         println!("Error: {:?}", e);
                         self.managed = None;
+                        Err(e.message().to_string())
                     }
                 }
             }
@@ -171,18 +195,182 @@ impl FetchedRouterStatus {
     }
 }
 
+impl FetchedRouterStatus {
+    pub fn principle_targets(&self) -> Vec<String> {
+        match &self.managed {
+            Some(balancer) => match &balancer.principle_target {
+                Some(principle_target) => principle_target.tag.clone(),
+                None => vec![],
+            },
+            None => vec![],
+        }
+    }
+
+    pub fn override_target(&self) -> Option<String> {
+        let balancer = self.managed.as_ref()?;
+        let override_info = balancer.r#override.as_ref()?;
+        match override_info.target.as_str() {
+            "" => None,
+            _ => Some(override_info.target.clone()),
+        }
+    }
+
+    pub fn selected_target(&self) -> Option<String> {
+        self.override_target()
+            .or_else(|| self.principle_targets().first().cloned())
+    }
+}
+
+const DOCUMENT_REFRESH_SECONDS: i64 = 30 * 60;
+const DOCUMENT_RETRY_SECONDS: i64 = 5 * 60;
+const DOCUMENT_FETCH_TIMEOUT_MILLIS: u32 = 15_000;
+/// Subscription documents fetched by the browser at the same time.
+pub const DOCUMENT_FETCH_CONCURRENCY: usize = 2;
+
+/// A subscription document fetched by the browser from the subscription URL. The core only
+/// reports server tags and metadata, so outbound configurations are decoded from here.
+///
+/// The browser fetches the URL directly, not through the subscription's `import_using_tag`,
+/// so this only happens when asked for or when enabled in the preferences.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SubscriptionDocument {
+    pub url: String,
+    pub fetched_at: i64,
+    pub result: Result<Vec<OutboundConfig>, String>,
+    /// Entries of the document that cannot be used, by entry name, with the reason.
+    pub rejected: Vec<(String, String)>,
+    pub usage: Option<SubscriptionUsage>,
+}
+
+/// Fetched documents and the names being fetched. Both are only changed by the reducer, so
+/// fetches started from the UI and from the background worker are not run twice.
+#[derive(PartialEq, Debug, Clone)]
+pub struct FetchedSubscriptionDocuments {
+    pub managed: BTreeMap<String, SubscriptionDocument>,
+    pub fetching: BTreeSet<String>,
+}
+
+/// Fetches the document text along with the `subscription-userinfo` header, if exposed.
+async fn fetch_document_text(url: &str) -> Result<(String, Option<String>), String> {
+    let request = Box::pin(async {
+        let response = gloo_net::http::Request::get(url)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.ok() {
+            return Err(format!(
+                "HTTP {} {}",
+                response.status(),
+                response.status_text()
+            ));
+        }
+        let userinfo = response.headers().get("subscription-userinfo");
+        let text = response.text().await.map_err(|e| e.to_string())?;
+        Ok((text, userinfo))
+    });
+    match select(request, TimeoutFuture::new(DOCUMENT_FETCH_TIMEOUT_MILLIS)).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err("timed out".to_string()),
+    }
+}
+
+pub async fn fetch_subscription_document(
+    name: &str,
+    url: String,
+    now: i64,
+) -> SubscriptionDocument {
+    let (parsed, usage) = match fetch_document_text(&url).await {
+        Ok((text, userinfo)) => (
+            parse_subscription_document(&text),
+            userinfo.as_deref().and_then(parse_subscription_userinfo),
+        ),
+        Err(e) => (Err(format!("Could not fetch the subscription document: {}", e)), None),
+    };
+    let rejected = match &parsed {
+        Ok(document) => document
+            .rejected()
+            .map(|(source, e)| (source.to_string(), e.to_string()))
+            .collect(),
+        Err(_) => vec![],
+    };
+    let result = parsed.map(|document| document.accepted().cloned().collect());
+    if let Err(e) = &result {
+        log!(<std::string::String as Into<JsValue>>::into(format!(
+            "subscription document {}: {}",
+            name, e
+        )));
+    }
+    SubscriptionDocument {
+        url,
+        fetched_at: now,
+        result,
+        rejected,
+        usage,
+    }
+}
+
+impl FetchedSubscriptionDocuments {
+    pub fn new() -> FetchedSubscriptionDocuments {
+        FetchedSubscriptionDocuments {
+            managed: BTreeMap::new(),
+            fetching: BTreeSet::new(),
+        }
+    }
+
+    /// Whether the document should be fetched again: it was never fetched, the URL or the
+    /// server list changed since, or it went stale.
+    pub fn needs_refresh(&self, name: &str, url: &str, servers_changed_at: i64, now: i64) -> bool {
+        if self.fetching.contains(name) {
+            return false;
+        }
+        match self.managed.get(name) {
+            None => true,
+            Some(document) => {
+                let max_age = match document.result {
+                    Ok(_) => DOCUMENT_REFRESH_SECONDS,
+                    Err(_) => DOCUMENT_RETRY_SECONDS,
+                };
+                document.url != url
+                    || servers_changed_at > document.fetched_at
+                    || now - document.fetched_at > max_age
+            }
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub struct CoreLink {
     pub fetched_measurement: FetchedMeasurement,
     pub fetched_subscription: FetchedSubscription,
     pub fetched_router_status: FetchedRouterStatus,
+    pub fetched_documents: FetchedSubscriptionDocuments,
+    pub watchdog: WatchdogState,
+    pub policy_log: PolicyLog,
+    pub schedule: ScheduleState,
+    pub subscription_changes: SubscriptionChangeTracker,
+    pub subscription_refresh: SubscriptionRefreshState,
+    pub connection_health: ConnectionHealth,
+    pub balancer_timeline: BalancerTimeline,
+    pub notifications: NotificationState,
+    pub alerts: AlertState,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct SubscriptionServerRef {
+    pub subscription_name: String,
+    pub server_name: String,
+    pub display_name: String,
+    pub server_metadata: HashMap<String, String>,
 }
 
 pub enum CoreLinkAction {
     SetPrimaryBalancerTarget(String),
-    RemoveSubscription(String),
     UpdateSubscription(String),
-    AddSubscription(String, String),
+    AddSubscription(subscription::ImportSource),
+    RestoreSubscription(subscription::ImportSource),
+    /// Replaces the first import source with the second, putting the first back when the core
+    /// refuses the second.
+    ReplaceSubscription(subscription::ImportSource, subscription::ImportSource),
 }
 
 async fn set_primary_balancer_target(grpc_client: GrpcClient, target: String) -> () {
@@ -212,7 +400,10 @@ async fn set_primary_balancer_target(grpc_client: GrpcClient, target: String) ->
     };
 }
 
-async fn add_subscription(grpc_client: GrpcClient, name: String, url: String) -> () {
+async fn add_subscription_source(
+    grpc_client: GrpcClient,
+    source: subscription::ImportSource,
+) -> Result<(), String> {
     let grpc_client_copy = grpc_client.client.clone();
 
     let mut received_client_lockguard = grpc_client_copy.lock().unwrap();
@@ -222,26 +413,59 @@ async fn add_subscription(grpc_client: GrpcClient, name: String, url: String) ->
             received_client_lockguard.deref_mut(),
         );
     let request = subscription::subscriptionmanager::command::AddTrackedSubscriptionRequest {
-        source: Some(subscription::ImportSource {
-            name: name.clone(),
-            url,
-            tag_prefix: format!("subscription_{}", name.clone()),
-            import_using_tag: "direct".to_string(),
-            default_expire_seconds: 3600,
-        }),
+        source: Some(source),
     };
     let response = subscription_client.add_tracked_subscription(request);
-    let _ = match response.await {
+    match response.await {
         Ok(response) => {
             println!("Add subscription response: {:?}", response);
+            Ok(())
         }
         Err(e) => {
             println!("Error: {:?}", e);
+            Err(e.message().to_string())
         }
-    };
+    }
 }
 
-async fn remove_subscription(grpc_client: GrpcClient, name: String) -> () {
+/// Adds a subscription from a full import source and fetches it right away. When `previous` is
+/// given, that subscription is removed first and added back if the core refuses the new source,
+/// so a failed replacement does not lose the subscription.
+async fn restore_subscription_source(
+    grpc_client: GrpcClient,
+    source: subscription::ImportSource,
+    previous: Option<subscription::ImportSource>,
+) -> Result<(), String> {
+    let name = source.name.clone();
+    if let Some(previous) = &previous {
+        remove_subscription(grpc_client.clone(), previous.name.clone()).await?;
+    }
+    if let Err(e) = add_subscription_source(grpc_client.clone(), source).await {
+        let Some(previous) = previous else {
+            return Err(e);
+        };
+        let previous_name = previous.name.clone();
+        let rollback = add_subscription_source(grpc_client.clone(), previous).await;
+        log!(<std::string::String as Into<JsValue>>::into(format!(
+            "replacing subscription {} failed: {}, {}",
+            name,
+            e,
+            match &rollback {
+                Ok(()) => "restored the previous source".to_string(),
+                Err(rollback_error) => {
+                    format!("restoring the previous source failed: {}", rollback_error)
+                }
+            }
+        )));
+        if rollback.is_ok() {
+            let _ = update_subscription(grpc_client, previous_name).await;
+        }
+        return Err(e);
+    }
+    update_subscription(grpc_client, name).await
+}
+
+pub(crate) async fn remove_subscription(grpc_client: GrpcClient, name: String) -> Result<(), String> {
     let grpc_client_copy = grpc_client.client.clone();
 
     let mut received_client_lockguard = grpc_client_copy.lock().unwrap();
@@ -254,17 +478,19 @@ async fn remove_subscription(grpc_client: GrpcClient, name: String) -> () {
         name: name.clone(),
     };
     let response = subscription_client.remove_tracked_subscription(request);
-    let _ = match response.await {
+    match response.await {
         Ok(response) => {
             println!("Remove subscription response: {:?}", response);
+            Ok(())
         }
         Err(e) => {
             println!("Error: {:?}", e);
+            Err(e.message().to_string())
         }
-    };
+    }
 }
 
-async fn update_subscription(grpc_client: GrpcClient, name: String) -> () {
+pub(crate) async fn update_subscription(grpc_client: GrpcClient, name: String) -> Result<(), String> {
     let grpc_client_copy = grpc_client.client.clone();
 
     let mut received_client_lockguard = grpc_client_copy.lock().unwrap();
@@ -277,14 +503,61 @@ async fn update_subscription(grpc_client: GrpcClient, name: String) -> () {
         name: name.clone(),
     };
     let response = subscription_client.update_tracked_subscription(request);
-    let _ = match response.await {
+    match response.await {
         Ok(response) => {
             println!("Remove subscription response: {:?}", response);
+            Ok(())
         }
         Err(e) => {
             println!("Error: {:?}", e);
+            Err(e.message().to_string())
         }
+    }
+}
+
+/// Installs the outbound in the core. The client is cloned out of the lock rather than borrowed,
+/// so the lock is not held while waiting for the response.
+pub(crate) async fn add_outbound(
+    grpc_client: GrpcClient,
+    tag: String,
+    config: OutboundConfig,
+) -> Result<(), String> {
+    let outbound = build_outbound_handler_config(&tag, &config)?;
+    let received_client = grpc_client.client.lock().unwrap().clone();
+
+    let mut handler_client =
+        proxyman::command::handler_service_client::HandlerServiceClient::new(received_client);
+    let request = proxyman::command::AddOutboundRequest {
+        outbound: Some(outbound),
     };
+    match handler_client.add_outbound(request).await {
+        Ok(response) => {
+            println!("Add outbound response: {:?}", response);
+            Ok(())
+        }
+        Err(e) => {
+            println!("Error: {:?}", e);
+            Err(e.message().to_string())
+        }
+    }
+}
+
+pub(crate) async fn remove_outbound(grpc_client: GrpcClient, tag: String) -> Result<(), String> {
+    let received_client = grpc_client.client.lock().unwrap().clone();
+
+    let mut handler_client =
+        proxyman::command::handler_service_client::HandlerServiceClient::new(received_client);
+    let request = proxyman::command::RemoveOutboundRequest { tag };
+    match handler_client.remove_outbound(request).await {
+        Ok(response) => {
+            println!("Remove outbound response: {:?}", response);
+            Ok(())
+        }
+        Err(e) => {
+            println!("Error: {:?}", e);
+            Err(e.message().to_string())
+        }
+    }
 }
 
 impl CoreLink {
@@ -293,22 +566,213 @@ impl CoreLink {
             fetched_measurement: FetchedMeasurement::new(),
             fetched_subscription: FetchedSubscription::new(),
             fetched_router_status: FetchedRouterStatus::new(),
+            fetched_documents: FetchedSubscriptionDocuments::new(),
+            watchdog: WatchdogState::new(),
+            policy_log: PolicyLog::new(),
+            schedule: ScheduleState::new(),
+            subscription_changes: SubscriptionChangeTracker::load(),
+            subscription_refresh: SubscriptionRefreshState::load(),
+            connection_health: ConnectionHealth::new(),
+            balancer_timeline: BalancerTimeline::new(),
+            notifications: NotificationState::new(),
+            alerts: AlertState::new(),
+        }
+    }
+
+    /// Every outbound tag the observatory or the balancer has reported.
+    pub fn reported_outbound_tags(&self) -> BTreeSet<String> {
+        let mut tags: BTreeSet<String> = self.fetched_measurement.managed.keys().cloned().collect();
+        tags.extend(self.fetched_router_status.principle_targets());
+        tags.extend(self.fetched_router_status.override_target());
+        tags
+    }
+
+    pub fn subscription_servers_by_outbound_tag(&self) -> BTreeMap<String, SubscriptionServerRef> {
+        let mut index = BTreeMap::new();
+        for (subscription_name, subscription) in self.fetched_subscription.managed.iter() {
+            let Some(tracked_subscription_status) = subscription else {
+                continue;
+            };
+            let Some(import_source) = &tracked_subscription_status.import_source else {
+                continue;
+            };
+            for (server_name, server_info) in tracked_subscription_status.servers.iter() {
+                let outbound_tag = format!("{}_{}", import_source.tag_prefix, server_info.tag);
+                let display_name = match server_info.server_metadata.get("DisplayName") {
+                    Some(display_name) => display_name.clone(),
+                    None => outbound_tag.clone(),
+                };
+                index.insert(
+                    outbound_tag,
+                    SubscriptionServerRef {
+                        subscription_name: subscription_name.clone(),
+                        server_name: server_name.clone(),
+                        display_name,
+                        server_metadata: server_info.server_metadata.clone(),
+                    },
+                );
+            }
+        }
+        index
+    }
+
+    /// The URL of every subscription that reports its import source.
+    pub fn subscription_urls(&self) -> BTreeMap<String, String> {
+        self.fetched_subscription
+            .managed
+            .iter()
+            .filter_map(|(name, subscription)| {
+                let import_source = subscription.as_ref()?.import_source.as_ref()?;
+                Some((name.clone(), import_source.url.clone()))
+            })
+            .collect()
+    }
+
+    /// Matches subscription servers to entries of the fetched subscription document. The core
+    /// reports no link between the two other than the display name, which providers do not keep
+    /// unique, so only names that occur once among the subscription's servers and once in the
+    /// document are matched. A wrong configuration is worse than none.
+    pub fn outbound_configs_by_outbound_tag(&self) -> BTreeMap<String, OutboundConfig> {
+        let server_index = self.subscription_servers_by_outbound_tag();
+        let mut server_name_counts: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+        for server_ref in server_index.values() {
+            *server_name_counts
+                .entry((&server_ref.subscription_name, &server_ref.display_name))
+                .or_default() += 1;
         }
+        let mut configs = BTreeMap::new();
+        for (outbound_tag, server_ref) in server_index.iter() {
+            let name_key = (
+                server_ref.subscription_name.as_str(),
+                server_ref.display_name.as_str(),
+            );
+            if server_name_counts[&name_key] != 1 {
+                continue;
+            }
+            let Some(document) = self.fetched_documents.managed.get(&server_ref.subscription_name)
+            else {
+                continue;
+            };
+            let Ok(document_configs) = &document.result else {
+                continue;
+            };
+            let mut matching = document_configs
+                .iter()
+                .filter(|config| config.name == server_ref.display_name);
+            if let (Some(config), None) = (matching.next(), matching.next()) {
+                configs.insert(outbound_tag.clone(), config.clone());
+            }
+        }
+        configs
     }
 
-    pub async fn apply_action(&self, grpc_client: GrpcClient, action: CoreLinkAction) {
+    /// Explains why `outbound_configs_by_outbound_tag` has no configuration for a server.
+    pub fn missing_outbound_config_reason(&self, server_ref: &SubscriptionServerRef) -> String {
+        let document = self.fetched_documents.managed.get(&server_ref.subscription_name);
+        let servers_with_name = self
+            .subscription_servers_by_outbound_tag()
+            .values()
+            .filter(|other| {
+                other.subscription_name == server_ref.subscription_name
+                    && other.display_name == server_ref.display_name
+            })
+            .count();
+        match document {
+            None if self.fetched_documents.fetching.contains(&server_ref.subscription_name) => {
+                "The subscription document is being fetched".to_string()
+            }
+            None => {
+                "The subscription document has not been fetched; use Fetch Document on the subscription"
+                    .to_string()
+            }
+            Some(SubscriptionDocument { result: Err(e), .. }) => e.clone(),
+            Some(_) if servers_with_name > 1 => format!(
+                "{} servers of this subscription are named \"{}\", so their configurations cannot be told apart",
+                servers_with_name, server_ref.display_name
+            ),
+            Some(SubscriptionDocument {
+                result: Ok(configs),
+                rejected,
+                ..
+            }) => {
+                if let Some((_, e)) = rejected
+                    .iter()
+                    .find(|(source, _)| source == &server_ref.display_name)
+                {
+                    return format!("The subscription document's entry cannot be used: {}", e);
+                }
+                let documents_with_name = configs
+                    .iter()
+                    .filter(|config| config.name == server_ref.display_name)
+                    .count();
+                match documents_with_name {
+                    0 => format!(
+                        "The subscription document has no server named \"{}\"",
+                        server_ref.display_name
+                    ),
+                    count => format!(
+                        "The subscription document has {} servers named \"{}\", so the configuration cannot be told apart",
+                        count, server_ref.display_name
+                    ),
+                }
+            }
+        }
+    }
+
+    pub fn policy_candidates(&self) -> Vec<PolicyCandidate> {
+        let server_index = self.subscription_servers_by_outbound_tag();
+        self.fetched_measurement
+            .managed
+            .values()
+            .map(|status| {
+                let server_ref = server_index.get(&status.outbound_tag);
+                PolicyCandidate {
+                    outbound_tag: status.outbound_tag.clone(),
+                    display_name: match server_ref {
+                        Some(server_ref) => server_ref.display_name.clone(),
+                        None => status.outbound_tag.clone(),
+                    },
+                    subscription_name: server_ref.map(|server_ref| server_ref.subscription_name.clone()),
+                    metadata: match server_ref {
+                        Some(server_ref) => server_ref.server_metadata.clone(),
+                        None => HashMap::new(),
+                    },
+                    alive: status.alive,
+                    delay: status.delay,
+                }
+            })
+            .collect()
+    }
+
+    /// Runs the action against the core. Returns the subscription it updated and the result,
+    /// for actions that update one, so failures can be reported.
+    pub async fn apply_action(
+        &self,
+        grpc_client: GrpcClient,
+        action: CoreLinkAction,
+    ) -> Option<(String, Result<(), String>)> {
         match action {
             CoreLinkAction::SetPrimaryBalancerTarget(target) => {
                 set_primary_balancer_target(grpc_client, target).await;
+                None
             }
-            CoreLinkAction::AddSubscription(name, url) => {
-                add_subscription(grpc_client, name, url).await;
-            }
-            CoreLinkAction::RemoveSubscription(name) => {
-                remove_subscription(grpc_client, name).await;
+            CoreLinkAction::AddSubscription(source) => {
+                let _ = add_subscription_source(grpc_client, source).await;
+                None
             }
             CoreLinkAction::UpdateSubscription(name) => {
-                update_subscription(grpc_client, name).await
+                let result = update_subscription(grpc_client, name.clone()).await;
+                Some((name, result))
+            }
+            CoreLinkAction::RestoreSubscription(source) => {
+                let name = source.name.clone();
+                let result = restore_subscription_source(grpc_client, source, None).await;
+                Some((name, result))
+            }
+            CoreLinkAction::ReplaceSubscription(previous, source) => {
+                let name = source.name.clone();
+                let result = restore_subscription_source(grpc_client, source, Some(previous)).await;
+                Some((name, result))
             }
         }
     }
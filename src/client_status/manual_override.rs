@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+/// A manual balancer override that should be cleared once `expires_at` (unix seconds) passes.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct OverrideExpiry {
+    pub target: String,
+    pub expires_at: i64,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum OverrideDuration {
+    FifteenMinutes,
+    OneHour,
+    UntilTomorrow,
+    UntilChanged,
+}
+
+impl OverrideDuration {
+    pub const ALL: [OverrideDuration; 4] = [
+        OverrideDuration::FifteenMinutes,
+        OverrideDuration::OneHour,
+        OverrideDuration::UntilTomorrow,
+        OverrideDuration::UntilChanged,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OverrideDuration::FifteenMinutes => "For 15 minutes",
+            OverrideDuration::OneHour => "For 1 hour",
+            OverrideDuration::UntilTomorrow => "Until tomorrow",
+            OverrideDuration::UntilChanged => "Until changed",
+        }
+    }
+
+    pub fn expiry_for(&self, target: &str) -> Option<OverrideExpiry> {
+        let now = js_sys::Date::now();
+        let expires_at_ms = match self {
+            OverrideDuration::FifteenMinutes => now + 15.0 * 60.0 * 1000.0,
+            OverrideDuration::OneHour => now + 60.0 * 60.0 * 1000.0,
+            OverrideDuration::UntilTomorrow => {
+                // Local midnight, the Date setters roll hour 24 over into the next day.
+                let date = js_sys::Date::new_0();
+                date.set_hours(24);
+                date.set_minutes(0);
+                date.set_seconds(0);
+                date.set_milliseconds(0);
+                date.get_time()
+            }
+            OverrideDuration::UntilChanged => return None,
+        };
+        Some(OverrideExpiry {
+            target: target.to_string(),
+            expires_at: (expires_at_ms / 1000.0) as i64,
+        })
+    }
+}
+
+/// What set the balancer override, so automation can tell a user's choice from its own. Each
+/// kind of automation only replaces an override when, in order of precedence:
+///
+/// 1. `Expiry` clears a timed override set by the user once it runs out.
+/// 2. `Watchdog` replaces any override whose target stops responding, which is what it is
+///    enabled for.
+/// 3. `Schedule` applies its mode whenever a scheduled change comes due.
+/// 4. `Policy` sets the override only when there is none or the policy set it itself, and only
+///    when its winner changes, so it never reverts anything above.
+///
+/// `User` overrides are chosen on this dashboard and always take effect.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum OverrideOrigin {
+    User,
+    Expiry,
+    Watchdog,
+    Schedule,
+    Policy,
+}
+
+impl OverrideOrigin {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OverrideOrigin::User => "Manual",
+            OverrideOrigin::Expiry => "Override expired",
+            OverrideOrigin::Watchdog => "Watchdog",
+            OverrideOrigin::Schedule => "Schedule",
+            OverrideOrigin::Policy => "Policy",
+        }
+    }
+}
+
+/// The last override change made from this dashboard. `target` is empty when it was cleared.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct OverrideRecord {
+    pub target: String,
+    pub origin: OverrideOrigin,
+}
+
+impl OverrideRecord {
+    /// The origin of `current_override` when it is still what was recorded. An override set
+    /// somewhere else, such as another dashboard, has no known origin.
+    pub fn origin_of(
+        record: Option<&OverrideRecord>,
+        current_override: Option<&String>,
+    ) -> Option<OverrideOrigin> {
+        let record = record?;
+        let current = current_override.map(String::as_str).unwrap_or("");
+        (record.target == current).then_some(record.origin)
+    }
+}
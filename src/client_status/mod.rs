@@ -1,24 +1,64 @@
 use crate::background::BackgroundWorker;
+use crate::client_status::bulk_operation::{BulkItemState, BulkOperation};
 use crate::client_status::core_link::{CoreLink, CoreLinkAction};
 use gloo_console::log;
 use std::rc::Rc;
 use wasm_bindgen::JsValue;
 use wasm_bindgen_futures::spawn_local;
 use yew::Reducible;
+pub mod alerts;
+pub mod balancer_timeline;
+pub mod bulk_operation;
+pub mod connection_health;
 pub mod core_link;
+pub mod manual_override;
+pub mod notifications;
+pub mod outbound_config;
+pub mod outbound_handler;
+pub mod policy;
+pub mod schedule;
+pub mod subscription_backup;
+pub mod subscription_changes;
+pub mod subscription_form;
+pub mod subscription_refresh;
+pub mod subscription_usage;
+pub mod preferences;
+pub mod route;
+pub mod storage;
 pub mod ui_status;
+pub mod watchdog;
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct ClientStatus {
     pub ui_status: ui_status::UIStatus,
     pub core_link: CoreLink,
+    pub preferences: preferences::Preferences,
+    pub bulk_operation: Option<BulkOperation>,
 }
 
 pub enum ClientStatusAction {
     SetCoreLink(CoreLink),
+    FetchSubscriptionDocuments(Vec<String>),
+    SetSubscriptionDocument(String, core_link::SubscriptionDocument),
+    RunScheduledRefresh(),
+    RecordScheduledRefresh(String, subscription_refresh::RefreshRun),
+    RecordSubscriptionUpdate(String, Result<(), String>),
+    ShowNotifications(Vec<balancer_timeline::BalancerSwitchEvent>),
     SetUIStatus(ui_status::UIStatus),
+    Navigate(route::Route),
+    SetPreferences(preferences::Preferences),
+    SetOverrideExpiry(Option<manual_override::OverrideExpiry>),
+    SetOverrideOrigin(manual_override::OverrideRecord),
     ApplyAction(CoreLinkAction),
+    AddManualOutbound(preferences::ManualOutbound),
+    FinishManualOutbound(preferences::ManualOutbound, Result<(), String>),
+    RemoveManualOutbound(String),
+    FinishRemovingManualOutbound(String, Result<(), String>),
     SyncNow(),
+    StartBulkOperation(BulkOperation),
+    SetBulkItemState(String, BulkItemState),
+    FinishBulkOperation(),
+    DismissBulkOperation(),
 }
 
 impl Reducible for ClientStatus {
@@ -27,32 +67,347 @@ impl Reducible for ClientStatus {
     fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
         match action {
             ClientStatusAction::SetCoreLink(core_link) => Rc::new(ClientStatus {
-                ui_status: self.ui_status.clone(),
-                core_link,
-            }),
-            ClientStatusAction::SetUIStatus(ui_status) => Rc::new(ClientStatus {
-                ui_status,
-                core_link: self.core_link.clone(),
+                // Documents, scheduled updates and notifications are handled outside of polls and
+                // only tracked here.
+                core_link: CoreLink {
+                    fetched_documents: self.core_link.fetched_documents.clone(),
+                    subscription_refresh: self.core_link.subscription_refresh.clone(),
+                    notifications: self.core_link.notifications.clone(),
+                    ..core_link
+                },
+                ..(*self).clone()
             }),
+            ClientStatusAction::FetchSubscriptionDocuments(names) => {
+                let urls = self.core_link.subscription_urls();
+                let sources: Vec<(String, String)> = names
+                    .into_iter()
+                    .filter(|name| !self.core_link.fetched_documents.fetching.contains(name))
+                    .filter_map(|name| Some((name.clone(), urls.get(&name)?.clone())))
+                    .collect();
+                if sources.is_empty() {
+                    return self;
+                }
+                let mut core_link = self.core_link.clone();
+                core_link
+                    .fetched_documents
+                    .fetching
+                    .extend(sources.iter().map(|(name, _)| name.clone()));
+                let background_refresh = crate::app::get_background_refresh();
+                spawn_local(async move {
+                    BackgroundWorker::fetch_subscription_documents(background_refresh, sources)
+                        .await;
+                });
+                Rc::new(ClientStatus {
+                    core_link,
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::SetSubscriptionDocument(name, document) => {
+                let mut core_link = self.core_link.clone();
+                core_link.fetched_documents.fetching.remove(&name);
+                core_link.fetched_documents.managed.insert(name, document);
+                Rc::new(ClientStatus {
+                    core_link,
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::SetUIStatus(ui_status) => {
+                ui_status.save();
+                Rc::new(ClientStatus {
+                    ui_status,
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::Navigate(route) => {
+                // Server links are plain `#/servers/...` hrefs, so the focus is taken from the
+                // route. That way bookmarks and back and forward highlight the server too.
+                let subscription_focused_outbound = match &route {
+                    route::Route::Server(outbound_tag) => outbound_tag.clone(),
+                    _ => self.ui_status.subscription_focused_outbound.clone(),
+                };
+                let ui_status = ui_status::UIStatus {
+                    route,
+                    subscription_focused_outbound,
+                    ..self.ui_status.clone()
+                };
+                ui_status.save();
+                Rc::new(ClientStatus {
+                    ui_status,
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::SetPreferences(preferences) => {
+                preferences.save();
+                Rc::new(ClientStatus {
+                    preferences,
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::SetOverrideExpiry(override_expiry) => {
+                let preferences = preferences::Preferences {
+                    override_expiry,
+                    ..self.preferences.clone()
+                };
+                preferences.save();
+                Rc::new(ClientStatus {
+                    preferences,
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::SetOverrideOrigin(override_origin) => {
+                let preferences = preferences::Preferences {
+                    override_origin: Some(override_origin),
+                    ..self.preferences.clone()
+                };
+                preferences.save();
+                Rc::new(ClientStatus {
+                    preferences,
+                    ..(*self).clone()
+                })
+            }
             ClientStatusAction::ApplyAction(core_link_action) => {
                 let core_link = self.core_link.clone();
+                let mut preferences = self.preferences.clone();
+                // Overrides applied from the UI are the user's. Any other override replaces a
+                // pending timed one.
+                if let CoreLinkAction::SetPrimaryBalancerTarget(target) = &core_link_action {
+                    if preferences
+                        .override_expiry
+                        .as_ref()
+                        .is_some_and(|expiry| &expiry.target != target)
+                    {
+                        preferences.override_expiry = None;
+                    }
+                    preferences.override_origin = Some(manual_override::OverrideRecord {
+                        target: target.clone(),
+                        origin: manual_override::OverrideOrigin::User,
+                    });
+                    preferences.save();
+                }
                 let background_refresh = crate::app::get_background_refresh();
                 {
                     let core_link_clone = core_link.clone();
                     spawn_local(async move {
                         BackgroundWorker::apply_action(background_refresh, move |grpc| {
                             spawn_local(async move {
-                                core_link_clone.apply_action(grpc, core_link_action).await;
+                                let update =
+                                    core_link_clone.apply_action(grpc, core_link_action).await;
+                                if let Some((name, result)) = update {
+                                    BackgroundWorker::report(
+                                        ClientStatusAction::RecordSubscriptionUpdate(name, result),
+                                    );
+                                }
                             });
                         })
                         .await;
                     });
                 }
                 Rc::new(ClientStatus {
-                    ui_status: self.ui_status.clone(),
                     core_link: core_link.clone(),
+                    preferences,
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::RunScheduledRefresh() => {
+                let mut core_link = self.core_link.clone();
+                let now = now_unix_seconds();
+                // Other tabs may have run or claimed updates since the last look.
+                core_link.subscription_refresh = self.core_link.subscription_refresh.reload();
+                let due = core_link.subscription_refresh.due(
+                    &self.preferences.subscription_refresh,
+                    self.core_link.fetched_subscription.managed.keys(),
+                    now,
+                );
+                if let Some(name) = &due {
+                    core_link.subscription_refresh.claim(name, now);
+                }
+                if core_link.subscription_refresh != self.core_link.subscription_refresh {
+                    core_link.subscription_refresh.save();
+                }
+                if let Some(name) = due {
+                    let background_refresh = crate::app::get_background_refresh();
+                    spawn_local(async move {
+                        BackgroundWorker::run_scheduled_refresh(background_refresh, name, now)
+                            .await;
+                    });
+                }
+                if core_link == self.core_link {
+                    return self;
+                }
+                Rc::new(ClientStatus {
+                    core_link,
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::RecordScheduledRefresh(name, run) => {
+                let mut core_link = self.core_link.clone();
+                core_link.subscription_refresh = self.core_link.subscription_refresh.reload();
+                core_link.subscription_refresh.record(name, run);
+                core_link.subscription_refresh.save();
+                Rc::new(ClientStatus {
+                    core_link,
+                    ..(*self).clone()
                 })
             }
+            ClientStatusAction::RecordSubscriptionUpdate(name, result) => match result {
+                Ok(()) => self,
+                Err(e) => self.notify(vec![notifications::Notice::subscription_update_failed(
+                    &name, &e,
+                )]),
+            },
+            ClientStatusAction::ShowNotifications(switches) => {
+                let mut core_link = self.core_link.clone();
+                let notices = core_link.notifications.detect(
+                    &core_link.fetched_router_status,
+                    &core_link.fetched_measurement,
+                    &core_link.connection_health,
+                    &switches,
+                );
+                Rc::new(ClientStatus {
+                    core_link,
+                    ..(*self).clone()
+                })
+                .notify(notices)
+            }
+            ClientStatusAction::AddManualOutbound(manual_outbound) => {
+                let background_refresh = crate::app::get_background_refresh();
+                spawn_local(async move {
+                    BackgroundWorker::add_manual_outbound(background_refresh, manual_outbound)
+                        .await;
+                });
+                Rc::new(ClientStatus {
+                    ui_status: ui_status::UIStatus {
+                        manual_outbound_error: None,
+                        ..self.ui_status.clone()
+                    },
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::RemoveManualOutbound(outbound_tag) => {
+                let background_refresh = crate::app::get_background_refresh();
+                spawn_local(async move {
+                    BackgroundWorker::remove_manual_outbound(background_refresh, outbound_tag)
+                        .await;
+                });
+                Rc::new(ClientStatus {
+                    ui_status: ui_status::UIStatus {
+                        manual_outbound_remove_error: None,
+                        ..self.ui_status.clone()
+                    },
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::FinishRemovingManualOutbound(outbound_tag, result) => match result {
+                // The record is kept while the core may still have the outbound, so it can be
+                // removed again.
+                Ok(()) => {
+                    let preferences = self.preferences.without_manual_outbound(&outbound_tag);
+                    preferences.save();
+                    Rc::new(ClientStatus {
+                        preferences,
+                        ..(*self).clone()
+                    })
+                }
+                Err(e) => Rc::new(ClientStatus {
+                    ui_status: ui_status::UIStatus {
+                        manual_outbound_remove_error: Some((outbound_tag, e)),
+                        ..self.ui_status.clone()
+                    },
+                    ..(*self).clone()
+                }),
+            },
+            ClientStatusAction::FinishManualOutbound(manual_outbound, result) => {
+                let outbound_tag = manual_outbound.outbound_tag.clone();
+                match result {
+                    // Only outbounds the core accepted are kept, with the link as entered.
+                    Ok(()) => {
+                        let is_form_entry = self.ui_status.manual_outbound_card_open
+                            && self.ui_status.manual_outbound_new_link.trim()
+                                == manual_outbound.share_link;
+                        let preferences = self.preferences.with_manual_outbound(manual_outbound);
+                        preferences.save();
+                        let ui_status = match is_form_entry {
+                            true => ui_status::UIStatus {
+                                manual_outbound_card_open: false,
+                                manual_outbound_new_link: "".to_string(),
+                                manual_outbound_new_name: "".to_string(),
+                                manual_outbound_error: None,
+                                ..self.ui_status.clone()
+                            },
+                            false => self.ui_status.clone(),
+                        };
+                        ui_status.save();
+                        Rc::new(ClientStatus {
+                            ui_status,
+                            preferences,
+                            ..(*self).clone()
+                        })
+                    }
+                    Err(e) => Rc::new(ClientStatus {
+                        ui_status: ui_status::UIStatus {
+                            manual_outbound_error: Some((outbound_tag, e)),
+                            ..self.ui_status.clone()
+                        },
+                        ..(*self).clone()
+                    }),
+                }
+            }
+            ClientStatusAction::StartBulkOperation(bulk_operation) => {
+                if self
+                    .bulk_operation
+                    .as_ref()
+                    .is_some_and(|running| !running.is_finished())
+                {
+                    return self;
+                }
+                let background_refresh = crate::app::get_background_refresh();
+                let kind = bulk_operation.kind;
+                let names = bulk_operation.pending_names();
+                spawn_local(async move {
+                    BackgroundWorker::run_bulk_operation(background_refresh, kind, names).await;
+                });
+                Rc::new(ClientStatus {
+                    bulk_operation: Some(bulk_operation),
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::SetBulkItemState(name, state) => {
+                let Some(mut bulk_operation) = self.bulk_operation.clone() else {
+                    return self;
+                };
+                bulk_operation.items.insert(name, state);
+                Rc::new(ClientStatus {
+                    bulk_operation: Some(bulk_operation),
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::FinishBulkOperation() => {
+                let Some(mut bulk_operation) = self.bulk_operation.clone() else {
+                    return self;
+                };
+                bulk_operation.finished_at = Some(now_unix_seconds());
+                // Removals can be undone once the core has accepted them.
+                let removed_sources = bulk_operation.removed_sources();
+                let ui_status = match removed_sources.is_empty() {
+                    true => self.ui_status.clone(),
+                    false => ui_status::UIStatus {
+                        subscription_removed: Some(ui_status::RemovedSubscription {
+                            sources: removed_sources,
+                            removed_at: now_unix_seconds(),
+                        }),
+                        ..self.ui_status.clone()
+                    },
+                };
+                Rc::new(ClientStatus {
+                    bulk_operation: Some(bulk_operation),
+                    ui_status,
+                    ..(*self).clone()
+                })
+            }
+            ClientStatusAction::DismissBulkOperation() => Rc::new(ClientStatus {
+                bulk_operation: None,
+                ..(*self).clone()
+            }),
             ClientStatusAction::SyncNow() => {
                 let core_link = self.core_link.clone();
                 let background_refresh = crate::app::get_background_refresh();
@@ -60,24 +415,45 @@ impl Reducible for ClientStatus {
                 background_refresh_value.as_ref().unwrap().refresh();
 
                 Rc::new(ClientStatus {
-                    ui_status: self.ui_status.clone(),
                     core_link: core_link.clone(),
+                    ..(*self).clone()
                 })
             }
         }
     }
 }
 
+pub fn now_unix_seconds() -> i64 {
+    (js_sys::Date::now() / 1000.0) as i64
+}
+
 impl ClientStatus {
+    /// Shows the notices the notification settings let through.
+    fn notify(self: Rc<Self>, notices: Vec<notifications::Notice>) -> Rc<Self> {
+        if notices.is_empty() {
+            return self;
+        }
+        let mut core_link = self.core_link.clone();
+        let admitted = core_link.notifications.admit(
+            &self.preferences.notifications,
+            notices,
+            now_unix_seconds(),
+        );
+        for notice in &admitted {
+            notifications::show_notification(notice);
+        }
+        Rc::new(ClientStatus {
+            core_link,
+            ..(*self).clone()
+        })
+    }
+
     pub fn new() -> ClientStatus {
         ClientStatus {
-            ui_status: ui_status::UIStatus {
-                active_window: String::from(""),
-                subscription_add_new_card_open: false,
-                subscription_add_new_name: String::from(""),
-                subscription_add_new_url: String::from(""),
-            },
+            ui_status: ui_status::UIStatus::load(),
             core_link: CoreLink::new(),
+            preferences: preferences::Preferences::load(),
+            bulk_operation: None,
         }
     }
 }
@@ -0,0 +1,231 @@
+use crate::client_status::balancer_timeline::BalancerSwitchEvent;
+use crate::client_status::connection_health::ConnectionHealth;
+use crate::client_status::core_link::{FetchedMeasurement, FetchedRouterStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+/// The same notification is not repeated within this many seconds.
+const NOTIFICATION_DEDUP_SECONDS: i64 = 10 * 60;
+/// At most `NOTIFICATION_RATE_LIMIT` notifications are shown per `NOTIFICATION_RATE_WINDOW_SECONDS`.
+const NOTIFICATION_RATE_LIMIT: usize = 5;
+const NOTIFICATION_RATE_WINDOW_SECONDS: i64 = 5 * 60;
+/// Failed polls in a row before the webcommander counts as unreachable.
+const UNREACHABLE_AFTER_FAILURES: u32 = 3;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub enum NotificationKind {
+    SelectedOutboundDead,
+    AutomaticSwitch,
+    SubscriptionUpdateFailed,
+    CommanderUnreachable,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 4] = [
+        NotificationKind::SelectedOutboundDead,
+        NotificationKind::AutomaticSwitch,
+        NotificationKind::SubscriptionUpdateFailed,
+        NotificationKind::CommanderUnreachable,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationKind::SelectedOutboundDead => "The selected outbound stops responding",
+            NotificationKind::AutomaticSwitch => "The outbound changes without a user",
+            NotificationKind::SubscriptionUpdateFailed => "A subscription update fails",
+            NotificationKind::CommanderUnreachable => "The webcommander becomes unreachable",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    pub kinds: BTreeSet<NotificationKind>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        NotificationSettings {
+            enabled: false,
+            kinds: NotificationKind::ALL.into_iter().collect(),
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn with_kind(&self, kind: NotificationKind, enabled: bool) -> NotificationSettings {
+        let mut kinds = self.kinds.clone();
+        if enabled {
+            kinds.insert(kind);
+        } else {
+            kinds.remove(&kind);
+        }
+        NotificationSettings {
+            kinds,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Notice {
+    pub kind: NotificationKind,
+    /// Identifies repeats of the same notice for de-duplication.
+    pub key: String,
+    pub title: String,
+    pub body: String,
+}
+
+impl Notice {
+    /// A failed update of the subscription, whether started by a user, a bulk operation, a
+    /// restore or the refresh schedule.
+    pub fn subscription_update_failed(name: &str, error: &str) -> Notice {
+        Notice {
+            kind: NotificationKind::SubscriptionUpdateFailed,
+            key: format!("update:{}", name),
+            title: format!("Updating {} failed", name),
+            body: error.to_string(),
+        }
+    }
+}
+
+/// Turns poll results into notices on the transition into a bad state, then de-duplicates and
+/// rate-limits them. Only changed by the reducer, so notices from polls and from subscription
+/// updates share one de-duplication and rate limit.
+#[derive(PartialEq, Debug, Clone)]
+pub struct NotificationState {
+    dead_target: Option<String>,
+    unreachable: bool,
+    sent: BTreeMap<String, i64>,
+    recent: Vec<i64>,
+    pub suppressed: u32,
+}
+
+impl NotificationState {
+    pub fn new() -> NotificationState {
+        NotificationState {
+            dead_target: None,
+            unreachable: false,
+            sent: BTreeMap::new(),
+            recent: vec![],
+            suppressed: 0,
+        }
+    }
+
+    pub fn detect(
+        &mut self,
+        router_status: &FetchedRouterStatus,
+        measurement: &FetchedMeasurement,
+        connection_health: &ConnectionHealth,
+        switches: &[BalancerSwitchEvent],
+    ) -> Vec<Notice> {
+        let mut notices = vec![];
+
+        let dead_target = router_status.selected_target().filter(|target| {
+            measurement
+                .managed
+                .get(target)
+                .is_some_and(|status| !status.alive)
+        });
+        if let Some(target) = &dead_target {
+            if self.dead_target.as_ref() != Some(target) {
+                notices.push(Notice {
+                    kind: NotificationKind::SelectedOutboundDead,
+                    key: format!("dead:{}", target),
+                    title: "Selected outbound is dead".to_string(),
+                    body: format!("The observatory reports {} as not responding", target),
+                });
+            }
+        }
+        self.dead_target = dead_target;
+
+        for switch in switches
+            .iter()
+            .filter(|switch| switch.kind.is_automatic() && !switch.overridden)
+        {
+            let previous = switch
+                .previous
+                .tag
+                .clone()
+                .unwrap_or_else(|| "none".to_string());
+            let new = switch.new.tag.clone().unwrap_or_else(|| "none".to_string());
+            notices.push(Notice {
+                kind: NotificationKind::AutomaticSwitch,
+                key: format!("switch:{}:{}", previous, new),
+                title: format!("Balancer switched ({})", switch.kind.label()),
+                body: format!("Traffic moved from {} to {}", previous, new),
+            });
+        }
+
+        let unreachable = connection_health.consecutive_failures >= UNREACHABLE_AFTER_FAILURES;
+        if unreachable && !self.unreachable {
+            notices.push(Notice {
+                kind: NotificationKind::CommanderUnreachable,
+                key: "unreachable".to_string(),
+                title: "Webcommander unreachable".to_string(),
+                body: format!(
+                    "{} polls failed: {}",
+                    connection_health.consecutive_failures, connection_health.last_error
+                ),
+            });
+        }
+        self.unreachable = unreachable;
+
+        notices
+    }
+
+    /// Keeps the notices the settings ask for, dropping repeats and anything over the rate limit.
+    pub fn admit(
+        &mut self,
+        settings: &NotificationSettings,
+        notices: Vec<Notice>,
+        now: i64,
+    ) -> Vec<Notice> {
+        self.recent
+            .retain(|at| now - at < NOTIFICATION_RATE_WINDOW_SECONDS);
+        self.sent
+            .retain(|_, at| now - *at < NOTIFICATION_DEDUP_SECONDS);
+        let mut admitted = vec![];
+        for notice in notices {
+            if !settings.enabled || !settings.kinds.contains(&notice.kind) {
+                continue;
+            }
+            if self.sent.contains_key(&notice.key) {
+                continue;
+            }
+            if self.recent.len() >= NOTIFICATION_RATE_LIMIT {
+                self.suppressed += 1;
+                continue;
+            }
+            self.sent.insert(notice.key.clone(), now);
+            self.recent.push(now);
+            admitted.push(notice);
+        }
+        admitted
+    }
+}
+
+/// Whether the browser offers the Notification API; it is missing outside secure contexts.
+pub fn notifications_supported() -> bool {
+    web_sys::window().is_some_and(|window| {
+        js_sys::Reflect::has(&window, &wasm_bindgen::JsValue::from_str("Notification"))
+            .unwrap_or(false)
+    })
+}
+
+pub fn notification_permission() -> Option<NotificationPermission> {
+    notifications_supported().then(Notification::permission)
+}
+
+pub fn show_notification(notice: &Notice) {
+    if notification_permission() != Some(NotificationPermission::Granted) {
+        return;
+    }
+    let options = NotificationOptions::new();
+    options.set_body(&notice.body);
+    options.set_tag(&notice.key);
+    let _ = Notification::new_with_options(&notice.title, &options);
+}
@@ -0,0 +1,957 @@
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The outbound configuration of a subscription server, decoded from the subscription document.
+///
+/// `user_id` holds the UUID for vmess/vless and the password for trojan/shadowsocks.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct OutboundConfig {
+    pub name: String,
+    pub protocol: String,
+    pub address: String,
+    pub port: u16,
+    pub user_id: String,
+    pub method: String,
+    pub transport: String,
+    pub transport_host: String,
+    pub transport_path: String,
+    pub security: String,
+    pub sni: String,
+}
+
+/// Identifies the physical endpoint behind an outbound; resold servers share the same key.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
+pub struct EndpointKey {
+    pub protocol: String,
+    pub address: String,
+    pub port: u16,
+    pub user_id: String,
+}
+
+impl OutboundConfig {
+    pub fn endpoint_key(&self) -> EndpointKey {
+        EndpointKey {
+            protocol: self.protocol.clone(),
+            address: self.address.to_lowercase(),
+            port: self.port,
+            user_id: self.user_id.clone(),
+        }
+    }
+}
+
+pub fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD]
+        .iter()
+        .find_map(|engine| engine.decode(&text).ok())
+}
+
+fn decode_base64_text(text: &str) -> Option<String> {
+    String::from_utf8(decode_base64(text)?).ok()
+}
+
+fn percent_decode(text: &str) -> String {
+    percent_decode_str(text).decode_utf8_lossy().to_string()
+}
+
+/// Splits `host:port`, accepting bracketed IPv6 addresses.
+fn parse_host_port(text: &str) -> Result<(String, u16), String> {
+    let (host, port) = match text.strip_prefix('[') {
+        Some(rest) => {
+            let (host, port) = rest
+                .split_once("]:")
+                .ok_or_else(|| format!("invalid address \"{}\"", text))?;
+            (host, port)
+        }
+        None => text
+            .rsplit_once(':')
+            .ok_or_else(|| format!("missing port in \"{}\"", text))?,
+    };
+    let port = port
+        .trim_end_matches('/')
+        .parse::<u16>()
+        .map_err(|_| format!("invalid port \"{}\"", port))?;
+    Ok((host.to_string(), port))
+}
+
+/// Only plain and TLS connections are represented; REALITY and similar need keys the
+/// configuration has no room for.
+fn supported_security(security: &str) -> Result<String, String> {
+    match security {
+        "" | "none" => Ok("".to_string()),
+        "tls" => Ok("tls".to_string()),
+        security => Err(format!("security \"{}\" is not supported", security)),
+    }
+}
+
+fn is_enabled_flag(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "1" | "true")
+}
+
+/// The parts shared by `vless://`, `trojan://` and SIP002 `ss://` links:
+/// `userinfo@host:port?query#name`.
+struct UrlLink {
+    user_info: String,
+    address: String,
+    port: u16,
+    query: HashMap<String, String>,
+    name: String,
+}
+
+fn parse_url_link(rest: &str) -> Result<UrlLink, String> {
+    let (rest, name) = match rest.split_once('#') {
+        Some((rest, name)) => (rest, percent_decode(name)),
+        None => (rest, "".to_string()),
+    };
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, query),
+        None => (rest, ""),
+    };
+    let (user_info, host_port) = rest
+        .rsplit_once('@')
+        .ok_or_else(|| "missing \"@\"".to_string())?;
+    let (address, port) = parse_host_port(host_port)?;
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (key.to_string(), percent_decode(value)),
+            None => (pair.to_string(), "".to_string()),
+        })
+        .collect();
+    Ok(UrlLink {
+        user_info: percent_decode(user_info),
+        address,
+        port,
+        query,
+        name,
+    })
+}
+
+impl UrlLink {
+    fn query(&self, key: &str) -> String {
+        self.query.get(key).cloned().unwrap_or_default()
+    }
+
+    /// Rejects options that change how the connection is made, as dropping them would produce a
+    /// configuration that cannot connect. The TLS fingerprint (`fp`) is left out on purpose, the
+    /// connection works without it.
+    fn check_options(&self) -> Result<(), String> {
+        if let Some(key) = ["pbk", "sid", "spx", "alpn"]
+            .into_iter()
+            .find(|key| !self.query(key).is_empty())
+        {
+            return Err(format!("option \"{}\" is not supported", key));
+        }
+        if is_enabled_flag(&self.query("allowInsecure")) {
+            return Err("skipping certificate verification is not supported".to_string());
+        }
+        match self.query("headerType").as_str() {
+            "" | "none" => Ok(()),
+            header_type => Err(format!("header type \"{}\" is not supported", header_type)),
+        }
+    }
+
+    fn into_config(self, protocol: &str, default_security: &str) -> Result<OutboundConfig, String> {
+        self.check_options()?;
+        let transport = match self.query("type").as_str() {
+            "" => "tcp".to_string(),
+            transport => transport.to_string(),
+        };
+        let security = match self.query("security").as_str() {
+            "" => default_security.to_string(),
+            security => supported_security(security)?,
+        };
+        let transport_path = match transport.as_str() {
+            "grpc" => self.query("serviceName"),
+            _ => self.query("path"),
+        };
+        Ok(OutboundConfig {
+            protocol: protocol.to_string(),
+            address: self.address.clone(),
+            port: self.port,
+            user_id: self.user_info.clone(),
+            method: match protocol {
+                "vless" => self.query("flow"),
+                _ => "".to_string(),
+            },
+            transport,
+            transport_host: self.query("host"),
+            transport_path,
+            security,
+            sni: match self.query("sni").as_str() {
+                "" => self.query("peer"),
+                sni => sni.to_string(),
+            },
+            name: self.name,
+        })
+    }
+}
+
+/// Accepts both numbers and strings, vmess links in the wild use either.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LooseValue {
+    Number(u64),
+    Text(String),
+}
+
+impl LooseValue {
+    fn text(&self) -> String {
+        match self {
+            LooseValue::Number(number) => number.to_string(),
+            LooseValue::Text(text) => text.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VmessLink {
+    #[serde(default)]
+    ps: Option<String>,
+    add: String,
+    port: LooseValue,
+    id: String,
+    #[serde(default)]
+    aid: Option<LooseValue>,
+    #[serde(default)]
+    scy: Option<String>,
+    #[serde(default)]
+    net: Option<String>,
+    #[serde(default)]
+    host: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    tls: Option<String>,
+    #[serde(default)]
+    sni: Option<String>,
+    #[serde(default, rename = "type")]
+    header_type: Option<String>,
+    #[serde(default)]
+    alpn: Option<String>,
+}
+
+fn parse_vmess_link(rest: &str) -> Result<OutboundConfig, String> {
+    let json = decode_base64_text(rest).ok_or_else(|| "invalid base64".to_string())?;
+    let link: VmessLink = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    let port = link
+        .port
+        .text()
+        .parse::<u16>()
+        .map_err(|_| format!("invalid port \"{}\"", link.port.text()))?;
+    // Only AEAD (alterId 0) is configured for vmess outbounds.
+    match link.aid.as_ref().map(LooseValue::text).as_deref() {
+        None | Some("") | Some("0") => {}
+        Some(aid) => return Err(format!("alterId {} is not supported", aid)),
+    }
+    match link.header_type.as_deref() {
+        None | Some("") | Some("none") => {}
+        Some(header_type) => {
+            return Err(format!("header type \"{}\" is not supported", header_type))
+        }
+    }
+    if link.alpn.as_deref().is_some_and(|alpn| !alpn.is_empty()) {
+        return Err("option \"alpn\" is not supported".to_string());
+    }
+    let security = supported_security(link.tls.as_deref().unwrap_or_default())?;
+    Ok(OutboundConfig {
+        name: link.ps.unwrap_or_default(),
+        protocol: "vmess".to_string(),
+        address: link.add,
+        port,
+        user_id: link.id,
+        method: link.scy.unwrap_or_else(|| "auto".to_string()),
+        transport: match link.net.unwrap_or_default().as_str() {
+            "" => "tcp".to_string(),
+            net => net.to_string(),
+        },
+        transport_host: link.host.unwrap_or_default(),
+        transport_path: link.path.unwrap_or_default(),
+        security,
+        sni: link.sni.unwrap_or_default(),
+    })
+}
+
+fn unsupported_plugin(plugin: &str) -> String {
+    let name = plugin.split(';').next().unwrap_or_default();
+    format!("shadowsocks plugin \"{}\" is not supported", name)
+}
+
+fn parse_shadowsocks_link(rest: &str) -> Result<OutboundConfig, String> {
+    let (body, name) = match rest.split_once('#') {
+        Some((body, name)) => (body, percent_decode(name)),
+        None => (rest, "".to_string()),
+    };
+    let (body, query) = body.split_once('?').unwrap_or((body, ""));
+    // Plugins such as obfs-local wrap the connection; without one the server cannot be reached.
+    if let Some(plugin) = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, value)| *key == "plugin" && !value.is_empty())
+        .map(|(_, value)| percent_decode(value))
+    {
+        return Err(unsupported_plugin(&plugin));
+    }
+    // SIP002 encodes only the user info, the legacy form encodes everything but the name.
+    let (user_info, host_port) = match body.rsplit_once('@') {
+        Some((user_info, host_port)) => (
+            decode_base64_text(user_info).unwrap_or_else(|| percent_decode(user_info)),
+            host_port.to_string(),
+        ),
+        None => {
+            let decoded = decode_base64_text(body).ok_or_else(|| "invalid base64".to_string())?;
+            let (user_info, host_port) = decoded
+                .rsplit_once('@')
+                .ok_or_else(|| "missing \"@\"".to_string())?;
+            (user_info.to_string(), host_port.to_string())
+        }
+    };
+    let (method, password) = user_info
+        .split_once(':')
+        .ok_or_else(|| "missing cipher".to_string())?;
+    let (address, port) = parse_host_port(&host_port)?;
+    Ok(OutboundConfig {
+        name,
+        protocol: "shadowsocks".to_string(),
+        address,
+        port,
+        user_id: password.to_string(),
+        method: method.to_string(),
+        transport: "tcp".to_string(),
+        ..OutboundConfig::default()
+    })
+}
+
+pub fn parse_share_link(link: &str) -> Result<OutboundConfig, String> {
+    let (scheme, rest) = link
+        .trim()
+        .split_once("://")
+        .ok_or_else(|| "not a share link".to_string())?;
+    match scheme.to_lowercase().as_str() {
+        "vmess" => parse_vmess_link(rest),
+        "vless" => parse_url_link(rest)?.into_config("vless", ""),
+        "trojan" => parse_url_link(rest)?.into_config("trojan", "tls"),
+        "ss" => parse_shadowsocks_link(rest),
+        other => Err(format!("unsupported scheme \"{}\"", other)),
+    }
+}
+
+#[derive(Deserialize)]
+struct Sip008Document {
+    servers: Vec<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct Sip008Server {
+    #[serde(default)]
+    remarks: Option<String>,
+    server: String,
+    server_port: u16,
+    password: String,
+    method: String,
+    #[serde(default)]
+    plugin: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ClashDocument {
+    proxies: Vec<serde_yaml_ng::Value>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ClashWebSocketOptions {
+    path: String,
+    headers: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ClashGrpcOptions {
+    #[serde(rename = "grpc-service-name")]
+    grpc_service_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ClashProxy {
+    name: String,
+    #[serde(rename = "type")]
+    proxy_type: String,
+    server: String,
+    port: u16,
+    #[serde(default)]
+    uuid: String,
+    #[serde(default)]
+    password: String,
+    #[serde(default)]
+    cipher: String,
+    #[serde(default)]
+    flow: String,
+    #[serde(default)]
+    network: String,
+    #[serde(default)]
+    tls: bool,
+    #[serde(default)]
+    servername: String,
+    #[serde(default)]
+    sni: String,
+    #[serde(default)]
+    ws_opts: ClashWebSocketOptions,
+    #[serde(default)]
+    grpc_opts: ClashGrpcOptions,
+    #[serde(default)]
+    plugin: String,
+    #[serde(default, rename = "alterId")]
+    alter_id: Option<LooseValue>,
+    #[serde(default)]
+    skip_cert_verify: bool,
+    #[serde(default)]
+    alpn: Vec<String>,
+    #[serde(default)]
+    reality_opts: Option<serde_yaml_ng::Value>,
+}
+
+impl ClashProxy {
+    fn into_config(self) -> Result<OutboundConfig, String> {
+        let (protocol, user_id, method, default_security) = match self.proxy_type.as_str() {
+            "vmess" => ("vmess", self.uuid, self.cipher, ""),
+            "vless" => ("vless", self.uuid, self.flow, ""),
+            "trojan" => ("trojan", self.password, "".to_string(), "tls"),
+            "ss" => ("shadowsocks", self.password, self.cipher, ""),
+            other => return Err(format!("unsupported proxy type \"{}\"", other)),
+        };
+        if !self.plugin.is_empty() {
+            return Err(unsupported_plugin(&self.plugin));
+        }
+        match self.alter_id.as_ref().map(LooseValue::text).as_deref() {
+            None | Some("") | Some("0") => {}
+            Some(alter_id) => return Err(format!("alterId {} is not supported", alter_id)),
+        }
+        if self.reality_opts.is_some() {
+            return Err("security \"reality\" is not supported".to_string());
+        }
+        if !self.alpn.is_empty() {
+            return Err("option \"alpn\" is not supported".to_string());
+        }
+        if self.skip_cert_verify {
+            return Err("skipping certificate verification is not supported".to_string());
+        }
+        let transport = match self.network.as_str() {
+            "" => "tcp".to_string(),
+            network => network.to_string(),
+        };
+        let (transport_host, transport_path) = match transport.as_str() {
+            "ws" => (
+                self.ws_opts.headers.get("Host").cloned().unwrap_or_default(),
+                self.ws_opts.path,
+            ),
+            "grpc" => ("".to_string(), self.grpc_opts.grpc_service_name),
+            _ => ("".to_string(), "".to_string()),
+        };
+        Ok(OutboundConfig {
+            name: self.name,
+            protocol: protocol.to_string(),
+            address: self.server,
+            port: self.port,
+            user_id,
+            method,
+            transport,
+            transport_host,
+            transport_path,
+            security: if self.tls { "tls" } else { default_security }.to_string(),
+            sni: match self.servername.as_str() {
+                "" => self.sni,
+                servername => servername.to_string(),
+            },
+        })
+    }
+}
+
+/// The encodings a subscription document can come in.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DocumentFormat {
+    Sip008,
+    ClashYaml,
+    ShareLinks,
+    Base64ShareLinks,
+}
+
+impl DocumentFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DocumentFormat::Sip008 => "SIP008 JSON",
+            DocumentFormat::ClashYaml => "Clash YAML",
+            DocumentFormat::ShareLinks => "Share links",
+            DocumentFormat::Base64ShareLinks => "Base64 encoded share links",
+        }
+    }
+}
+
+/// One server entry of a subscription document. `source` names the entry so rejected entries can
+/// still be pointed at, without the credentials a share link carries.
+#[derive(PartialEq, Debug, Clone)]
+pub struct DocumentEntry {
+    pub source: String,
+    pub result: Result<OutboundConfig, String>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct ParsedDocument {
+    pub format: DocumentFormat,
+    pub entries: Vec<DocumentEntry>,
+}
+
+impl ParsedDocument {
+    pub fn accepted(&self) -> impl Iterator<Item = &OutboundConfig> {
+        self.entries.iter().filter_map(|entry| entry.result.as_ref().ok())
+    }
+
+    pub fn rejected(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().filter_map(|entry| match &entry.result {
+            Ok(_) => None,
+            Err(e) => Some((entry.source.as_str(), e.as_str())),
+        })
+    }
+}
+
+/// Rejects entries that decode but could never be dialed.
+fn check_entry(config: OutboundConfig) -> Result<OutboundConfig, String> {
+    if config.address.is_empty() {
+        return Err("missing server address".to_string());
+    }
+    if config.port == 0 {
+        return Err("port 0 is not usable".to_string());
+    }
+    if config.user_id.is_empty() {
+        return Err("missing user ID or password".to_string());
+    }
+    Ok(config)
+}
+
+fn structured_entry_name(value: &impl Serialize, key: &str, index: usize) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.get(key).and_then(|name| name.as_str().map(str::to_string)))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("entry #{}", index + 1))
+}
+
+fn parse_sip008_document(text: &str) -> Result<Vec<DocumentEntry>, String> {
+    let document: Sip008Document = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    Ok(document
+        .servers
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| DocumentEntry {
+            source: structured_entry_name(&value, "remarks", index),
+            result: serde_json::from_value::<Sip008Server>(value)
+                .map_err(|e| e.to_string())
+                .and_then(|server| match server.plugin.as_deref() {
+                    Some(plugin) if !plugin.is_empty() => Err(unsupported_plugin(plugin)),
+                    _ => Ok(server),
+                })
+                .map(|server| OutboundConfig {
+                    name: server.remarks.unwrap_or_default(),
+                    protocol: "shadowsocks".to_string(),
+                    address: server.server,
+                    port: server.server_port,
+                    user_id: server.password,
+                    method: server.method,
+                    transport: "tcp".to_string(),
+                    ..OutboundConfig::default()
+                }),
+        })
+        .collect())
+}
+
+fn parse_clash_document(text: &str) -> Result<Vec<DocumentEntry>, String> {
+    let document: ClashDocument = serde_yaml_ng::from_str(text).map_err(|e| e.to_string())?;
+    Ok(document
+        .proxies
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| DocumentEntry {
+            source: structured_entry_name(&value, "name", index),
+            result: serde_yaml_ng::from_value::<ClashProxy>(value)
+                .map_err(|e| e.to_string())
+                .and_then(ClashProxy::into_config),
+        })
+        .collect())
+}
+
+/// Names a share link entry without repeating the link, which carries the user ID or password:
+/// the server name when the link has one, otherwise the scheme and the entry's position.
+fn share_link_entry_name(
+    line: &str,
+    result: &Result<OutboundConfig, String>,
+    index: usize,
+) -> String {
+    let name = match result {
+        Ok(config) => config.name.clone(),
+        Err(_) => line
+            .split_once('#')
+            .map(|(_, name)| percent_decode(name))
+            .unwrap_or_default(),
+    };
+    if !name.trim().is_empty() {
+        return name;
+    }
+    match line.split_once("://") {
+        Some((scheme, _)) => format!("{}:// link, entry #{}", scheme, index + 1),
+        None => format!("entry #{}", index + 1),
+    }
+}
+
+fn parse_share_link_list(text: &str) -> Vec<DocumentEntry> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(index, line)| {
+            let result = parse_share_link(line);
+            DocumentEntry {
+                source: share_link_entry_name(line, &result, index),
+                result,
+            }
+        })
+        .collect()
+}
+
+fn is_clash_document(text: &str) -> bool {
+    text.lines().any(|line| line.starts_with("proxies:"))
+}
+
+/// Decodes every entry of a subscription document: a SIP008 JSON document, a clash style YAML
+/// document with a `proxies` list, or a list of share links that is optionally base64 encoded.
+pub fn parse_document_entries(text: &str) -> Result<ParsedDocument, String> {
+    let text = text.trim();
+    let (format, entries) = if text.starts_with('{') {
+        (DocumentFormat::Sip008, parse_sip008_document(text)?)
+    } else if is_clash_document(text) {
+        (DocumentFormat::ClashYaml, parse_clash_document(text)?)
+    } else if text.contains("://") {
+        (DocumentFormat::ShareLinks, parse_share_link_list(text))
+    } else {
+        let decoded = decode_base64_text(text).ok_or_else(|| {
+            "neither share links, SIP008, clash YAML nor base64 encoded share links".to_string()
+        })?;
+        (DocumentFormat::Base64ShareLinks, parse_share_link_list(&decoded))
+    };
+    let entries = entries
+        .into_iter()
+        .map(|entry| DocumentEntry {
+            result: entry.result.and_then(check_entry),
+            ..entry
+        })
+        .collect();
+    Ok(ParsedDocument { format, entries })
+}
+
+/// Parses a subscription document, failing when none of its entries can be used.
+pub fn parse_subscription_document(text: &str) -> Result<ParsedDocument, String> {
+    let document = parse_document_entries(text)?;
+    if document.accepted().next().is_none() {
+        return Err("no supported servers found".to_string());
+    }
+    Ok(document)
+}
+
+/// Groups outbound tags that point at the same endpoint, keeping only groups of two or more.
+pub fn group_duplicate_endpoints(
+    configs: &BTreeMap<String, OutboundConfig>,
+) -> Vec<(EndpointKey, Vec<String>)> {
+    let mut groups: BTreeMap<EndpointKey, Vec<String>> = BTreeMap::new();
+    for (outbound_tag, config) in configs.iter() {
+        groups
+            .entry(config.endpoint_key())
+            .or_default()
+            .push(outbound_tag.clone());
+    }
+    groups
+        .into_iter()
+        .filter(|(_, outbound_tags)| outbound_tags.len() > 1)
+        .collect()
+}
+
+/// Characters left unescaped in share link components, the RFC 3986 unreserved set.
+const SHARE_LINK_ESCAPE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn escape(text: &str) -> String {
+    utf8_percent_encode(text, SHARE_LINK_ESCAPE).to_string()
+}
+
+#[derive(Serialize)]
+struct VmessShareLink<'a> {
+    v: &'a str,
+    ps: &'a str,
+    add: &'a str,
+    port: String,
+    id: &'a str,
+    aid: &'a str,
+    scy: &'a str,
+    net: &'a str,
+    #[serde(rename = "type")]
+    header_type: &'a str,
+    host: &'a str,
+    path: &'a str,
+    tls: &'a str,
+    sni: &'a str,
+}
+
+impl OutboundConfig {
+    fn host_port(&self) -> String {
+        if self.address.contains(':') {
+            format!("[{}]:{}", self.address, self.port)
+        } else {
+            format!("{}:{}", self.address, self.port)
+        }
+    }
+
+    fn share_link_query(&self) -> String {
+        let mut query = vec![format!("type={}", escape(&self.transport))];
+        if self.protocol == "vless" {
+            query.insert(0, "encryption=none".to_string());
+            if !self.method.is_empty() {
+                query.push(format!("flow={}", escape(&self.method)));
+            }
+        }
+        query.push(format!(
+            "security={}",
+            if self.security.is_empty() { "none" } else { &self.security }
+        ));
+        for (key, value) in [
+            ("sni", &self.sni),
+            ("host", &self.transport_host),
+            (
+                if self.transport == "grpc" { "serviceName" } else { "path" },
+                &self.transport_path,
+            ),
+        ] {
+            if !value.is_empty() {
+                query.push(format!("{}={}", key, escape(value)));
+            }
+        }
+        query.join("&")
+    }
+
+    /// Encodes the configuration as the share link format `parse_share_link` reads.
+    pub fn to_share_link(&self) -> Result<String, String> {
+        if !matches!(self.security.as_str(), "" | "tls") {
+            return Err(format!("security \"{}\" is not supported", self.security));
+        }
+        match self.protocol.as_str() {
+            "vmess" => {
+                let link = VmessShareLink {
+                    v: "2",
+                    ps: &self.name,
+                    add: &self.address,
+                    port: self.port.to_string(),
+                    id: &self.user_id,
+                    aid: "0",
+                    scy: &self.method,
+                    net: &self.transport,
+                    header_type: "none",
+                    host: &self.transport_host,
+                    path: &self.transport_path,
+                    tls: &self.security,
+                    sni: &self.sni,
+                };
+                let json = serde_json::to_string(&link).map_err(|e| e.to_string())?;
+                Ok(format!("vmess://{}", STANDARD.encode(json)))
+            }
+            "vless" | "trojan" => Ok(format!(
+                "{}://{}@{}?{}#{}",
+                self.protocol,
+                escape(&self.user_id),
+                self.host_port(),
+                self.share_link_query(),
+                escape(&self.name)
+            )),
+            "shadowsocks" => Ok(format!(
+                "ss://{}@{}#{}",
+                URL_SAFE_NO_PAD.encode(format!("{}:{}", self.method, self.user_id)),
+                self.host_port(),
+                escape(&self.name)
+            )),
+            other => Err(format!("{} servers cannot be shared as a link", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(protocol: &str) -> OutboundConfig {
+        OutboundConfig {
+            name: "Tokyo 1 [JP]".to_string(),
+            protocol: protocol.to_string(),
+            address: "example.com".to_string(),
+            port: 443,
+            user_id: "b831381d-6324-4d53-ad4f-8cda48b30811".to_string(),
+            method: "".to_string(),
+            transport: "tcp".to_string(),
+            transport_host: "".to_string(),
+            transport_path: "".to_string(),
+            security: "tls".to_string(),
+            sni: "example.com".to_string(),
+        }
+    }
+
+    fn assert_round_trip(config: OutboundConfig) {
+        let link = config.to_share_link().unwrap();
+        assert_eq!(parse_share_link(&link), Ok(config), "{}", link);
+    }
+
+    #[test]
+    fn share_links_round_trip() {
+        assert_round_trip(OutboundConfig {
+            method: "auto".to_string(),
+            transport: "ws".to_string(),
+            transport_host: "cdn.example.com".to_string(),
+            transport_path: "/ray?ed=2048".to_string(),
+            ..config("vmess")
+        });
+        assert_round_trip(OutboundConfig {
+            transport: "grpc".to_string(),
+            transport_path: "service".to_string(),
+            ..config("vless")
+        });
+        assert_round_trip(OutboundConfig {
+            security: "".to_string(),
+            sni: "".to_string(),
+            ..config("vless")
+        });
+        assert_round_trip(OutboundConfig {
+            address: "2001:db8::1".to_string(),
+            user_id: "pass word/with:symbols".to_string(),
+            ..config("trojan")
+        });
+        assert_round_trip(OutboundConfig {
+            user_id: "secret:with:colons".to_string(),
+            method: "chacha20-ietf-poly1305".to_string(),
+            security: "".to_string(),
+            sni: "".to_string(),
+            ..config("shadowsocks")
+        });
+    }
+
+    #[test]
+    fn refuses_to_share_unsupported_security() {
+        let config = OutboundConfig {
+            security: "reality".to_string(),
+            ..config("vless")
+        };
+        assert!(config.to_share_link().is_err());
+    }
+
+    #[test]
+    fn rejects_options_that_cannot_be_kept() {
+        for link in [
+            "vless://id@example.com:443?security=reality&pbk=key&sid=01#a",
+            "vless://id@example.com:443?security=tls&alpn=h2#a",
+            "trojan://password@example.com:443?allowInsecure=1#a",
+            "vless://id@example.com:443?type=tcp&headerType=http#a",
+            "ss://YWVzLTI1Ni1nY206cGFzcw@example.com:8388?plugin=obfs-local%3Bobfs%3Dhttp#a",
+        ] {
+            assert!(parse_share_link(link).is_err(), "{}", link);
+        }
+        let vmess = STANDARD.encode(r#"{"add":"example.com","port":"443","id":"id","aid":"64"}"#);
+        assert_eq!(
+            parse_share_link(&format!("vmess://{}", vmess)),
+            Err("alterId 64 is not supported".to_string())
+        );
+    }
+
+    #[test]
+    fn accepts_the_legacy_shadowsocks_form() {
+        let link = format!(
+            "ss://{}#Legacy",
+            STANDARD.encode("aes-256-gcm:pass@198.51.100.1:8388")
+        );
+        let config = parse_share_link(&link).unwrap();
+        assert_eq!(config.method, "aes-256-gcm");
+        assert_eq!(config.user_id, "pass");
+        assert_eq!(config.address, "198.51.100.1");
+        assert_eq!(config.port, 8388);
+        assert_eq!(config.name, "Legacy");
+    }
+
+    #[test]
+    fn detects_document_formats() {
+        let links =
+            "vless://id@example.com:443?security=tls#One\ntrojan://pass@example.com:443#Two";
+        let sip008 = r#"{"version":1,"servers":[{"remarks":"One","server":"example.com","server_port":8388,"password":"pass","method":"aes-256-gcm"}]}"#;
+        let clash = "port: 7890\nproxies:\n  - name: One\n    type: trojan\n    server: example.com\n    port: 443\n    password: pass\n";
+        for (text, format) in [
+            (links.to_string(), DocumentFormat::ShareLinks),
+            (STANDARD.encode(links), DocumentFormat::Base64ShareLinks),
+            (sip008.to_string(), DocumentFormat::Sip008),
+            (clash.to_string(), DocumentFormat::ClashYaml),
+        ] {
+            let document = parse_document_entries(&text).unwrap();
+            assert_eq!(document.format, format);
+            assert_eq!(document.accepted().next().unwrap().name, "One");
+        }
+        assert!(parse_document_entries("not a document").is_err());
+    }
+
+    #[test]
+    fn rejected_entries_do_not_repeat_credentials() {
+        let document = parse_document_entries(
+            "vless://secret-id@example.com:443?security=reality#Reality\nvless://secret-id@:443",
+        )
+        .unwrap();
+        let rejected: Vec<_> = document.rejected().collect();
+        assert_eq!(rejected.len(), 2);
+        assert_eq!(rejected[0].0, "Reality");
+        assert_eq!(rejected[1].0, "vless:// link, entry #2");
+        assert!(rejected
+            .iter()
+            .all(|(source, _)| !source.contains("secret-id")));
+    }
+
+    #[test]
+    fn rejects_clash_proxies_with_unsupported_options() {
+        let document = parse_document_entries(
+            "proxies:\n  - name: Plugin\n    type: ss\n    server: example.com\n    port: 8388\n    cipher: aes-256-gcm\n    password: pass\n    plugin: obfs\n  - name: Legacy\n    type: vmess\n    server: example.com\n    port: 443\n    uuid: id\n    alterId: 64\n",
+        )
+        .unwrap();
+        assert_eq!(document.accepted().count(), 0);
+        assert_eq!(document.rejected().count(), 2);
+    }
+
+    #[test]
+    fn groups_duplicate_endpoints() {
+        let configs = BTreeMap::from([
+            ("a".to_string(), config("trojan")),
+            (
+                "b".to_string(),
+                OutboundConfig {
+                    name: "Resold".to_string(),
+                    address: "EXAMPLE.com".to_string(),
+                    ..config("trojan")
+                },
+            ),
+            (
+                "c".to_string(),
+                OutboundConfig {
+                    port: 8443,
+                    ..config("trojan")
+                },
+            ),
+        ]);
+        let groups = group_duplicate_endpoints(&configs);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].1, vec!["a".to_string(), "b".to_string()]);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::client_status::outbound_config::OutboundConfig;
+use crate::grpc::proto::v2ray::core;
+use crate::grpc::proto::v2ray::core::common::net::{ip_or_domain, IpOrDomain};
+use crate::grpc::proto::v2ray::core::common::protocol::{
+    SecurityConfig, SecurityType, ServerEndpoint, User,
+};
+use crate::grpc::proto::v2ray::core::proxy;
+use crate::grpc::proto::v2ray::core::transport::internet;
+use prost::Message;
+use std::net::IpAddr;
+
+/// Outbounds added from share links are tagged with this prefix, which the "subscriptions"
+/// balancer already selects.
+pub const MANUAL_OUTBOUND_TAG_PREFIX: &str = "subscription_manual_";
+
+/// Builds the full outbound tag from the user supplied name, replacing characters that would not
+/// survive in a tag.
+pub fn manual_outbound_tag(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '_',
+        })
+        .collect();
+    format!("{}{}", MANUAL_OUTBOUND_TAG_PREFIX, name)
+}
+
+fn typed_message<M: Message>(message_name: &str, message: &M) -> prost_types::Any {
+    prost_types::Any {
+        type_url: format!("types.v2fly.org/{}", message_name),
+        value: message.encode_to_vec(),
+    }
+}
+
+fn server_address(address: &str) -> IpOrDomain {
+    let address = match address
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    {
+        Ok(IpAddr::V4(ip)) => ip_or_domain::Address::Ip(ip.octets().to_vec()),
+        Ok(IpAddr::V6(ip)) => ip_or_domain::Address::Ip(ip.octets().to_vec()),
+        Err(_) => ip_or_domain::Address::Domain(address.to_string()),
+    };
+    IpOrDomain {
+        address: Some(address),
+    }
+}
+
+fn server_endpoint(config: &OutboundConfig, account: prost_types::Any) -> ServerEndpoint {
+    ServerEndpoint {
+        address: Some(server_address(&config.address)),
+        port: config.port as u32,
+        user: vec![User {
+            account: Some(account),
+            ..Default::default()
+        }],
+    }
+}
+
+fn vmess_security(method: &str) -> Result<SecurityType, String> {
+    match method {
+        "" | "auto" => Ok(SecurityType::Auto),
+        "aes-128-gcm" => Ok(SecurityType::Aes128Gcm),
+        "chacha20-poly1305" => Ok(SecurityType::Chacha20Poly1305),
+        "none" => Ok(SecurityType::None),
+        "zero" => Ok(SecurityType::Zero),
+        method => Err(format!("unsupported vmess security \"{}\"", method)),
+    }
+}
+
+fn shadowsocks_cipher(method: &str) -> Result<proxy::shadowsocks::CipherType, String> {
+    match method {
+        "aes-128-gcm" => Ok(proxy::shadowsocks::CipherType::Aes128Gcm),
+        "aes-256-gcm" => Ok(proxy::shadowsocks::CipherType::Aes256Gcm),
+        "chacha20-poly1305" | "chacha20-ietf-poly1305" => {
+            Ok(proxy::shadowsocks::CipherType::Chacha20Poly1305)
+        }
+        "none" | "plain" => Ok(proxy::shadowsocks::CipherType::None),
+        method => Err(format!("unsupported shadowsocks cipher \"{}\"", method)),
+    }
+}
+
+fn proxy_settings(config: &OutboundConfig) -> Result<prost_types::Any, String> {
+    match config.protocol.as_str() {
+        "vmess" => {
+            let account = proxy::vmess::Account {
+                id: config.user_id.clone(),
+                security_settings: Some(SecurityConfig {
+                    r#type: vmess_security(&config.method)? as i32,
+                }),
+                ..Default::default()
+            };
+            let account = typed_message("v2ray.core.proxy.vmess.Account", &account);
+            Ok(typed_message(
+                "v2ray.core.proxy.vmess.outbound.Config",
+                &proxy::vmess::outbound::Config {
+                    receiver: vec![server_endpoint(config, account)],
+                },
+            ))
+        }
+        "vless" => {
+            if !config.method.is_empty() {
+                return Err(format!("vless flow \"{}\" is not supported", config.method));
+            }
+            let account = proxy::vless::Account {
+                id: config.user_id.clone(),
+                flow: "".to_string(),
+                encryption: "none".to_string(),
+            };
+            let account = typed_message("v2ray.core.proxy.vless.Account", &account);
+            Ok(typed_message(
+                "v2ray.core.proxy.vless.outbound.Config",
+                &proxy::vless::outbound::Config {
+                    vnext: vec![server_endpoint(config, account)],
+                },
+            ))
+        }
+        "trojan" => {
+            let account = proxy::trojan::Account {
+                password: config.user_id.clone(),
+            };
+            let account = typed_message("v2ray.core.proxy.trojan.Account", &account);
+            Ok(typed_message(
+                "v2ray.core.proxy.trojan.ClientConfig",
+                &proxy::trojan::ClientConfig {
+                    server: vec![server_endpoint(config, account)],
+                },
+            ))
+        }
+        "shadowsocks" => {
+            let account = proxy::shadowsocks::Account {
+                password: config.user_id.clone(),
+                cipher_type: shadowsocks_cipher(&config.method)? as i32,
+                ..Default::default()
+            };
+            let account = typed_message("v2ray.core.proxy.shadowsocks.Account", &account);
+            Ok(typed_message(
+                "v2ray.core.proxy.shadowsocks.ClientConfig",
+                &proxy::shadowsocks::ClientConfig {
+                    server: vec![server_endpoint(config, account)],
+                },
+            ))
+        }
+        protocol => Err(format!("unsupported protocol \"{}\"", protocol)),
+    }
+}
+
+fn stream_settings(config: &OutboundConfig) -> Result<internet::StreamConfig, String> {
+    let (protocol_name, transport_settings) = match config.transport.as_str() {
+        "" | "tcp" => ("tcp", vec![]),
+        "ws" => {
+            let mut header = vec![];
+            if !config.transport_host.is_empty() {
+                header.push(internet::websocket::Header {
+                    key: "Host".to_string(),
+                    value: config.transport_host.clone(),
+                });
+            }
+            let settings = internet::websocket::Config {
+                path: config.transport_path.clone(),
+                header,
+                ..Default::default()
+            };
+            let settings =
+                typed_message("v2ray.core.transport.internet.websocket.Config", &settings);
+            (
+                "websocket",
+                vec![internet::TransportConfig {
+                    protocol_name: "websocket".to_string(),
+                    settings: Some(settings),
+                    ..Default::default()
+                }],
+            )
+        }
+        "grpc" => {
+            let settings = internet::grpc::encoding::Config {
+                host: config.transport_host.clone(),
+                service_name: config.transport_path.clone(),
+            };
+            let settings = typed_message(
+                "v2ray.core.transport.internet.grpc.encoding.Config",
+                &settings,
+            );
+            (
+                "gun",
+                vec![internet::TransportConfig {
+                    protocol_name: "gun".to_string(),
+                    settings: Some(settings),
+                    ..Default::default()
+                }],
+            )
+        }
+        transport => return Err(format!("unsupported transport \"{}\"", transport)),
+    };
+
+    let (security_type, security_settings) = match config.security.as_str() {
+        "" => ("".to_string(), vec![]),
+        "tls" => {
+            let server_name = [&config.sni, &config.transport_host, &config.address]
+                .into_iter()
+                .find(|name| !name.is_empty())
+                .cloned()
+                .unwrap_or_default();
+            let settings = internet::tls::Config {
+                server_name,
+                ..Default::default()
+            };
+            (
+                "v2ray.core.transport.internet.tls.Config".to_string(),
+                vec![typed_message(
+                    "v2ray.core.transport.internet.tls.Config",
+                    &settings,
+                )],
+            )
+        }
+        security => return Err(format!("unsupported security \"{}\"", security)),
+    };
+
+    Ok(internet::StreamConfig {
+        protocol_name: protocol_name.to_string(),
+        transport_settings,
+        security_type,
+        security_settings,
+        ..Default::default()
+    })
+}
+
+/// Translates a decoded share link into the handler config accepted by `AddOutbound`.
+pub fn build_outbound_handler_config(
+    tag: &str,
+    config: &OutboundConfig,
+) -> Result<core::OutboundHandlerConfig, String> {
+    let sender_settings = core::app::proxyman::SenderConfig {
+        stream_settings: Some(stream_settings(config)?),
+        ..Default::default()
+    };
+    Ok(core::OutboundHandlerConfig {
+        tag: tag.to_string(),
+        sender_settings: Some(typed_message(
+            "v2ray.core.app.proxyman.SenderConfig",
+            &sender_settings,
+        )),
+        proxy_settings: Some(proxy_settings(config)?),
+        comment: config.name.clone(),
+        ..Default::default()
+    })
+}
@@ -0,0 +1,505 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const POLICY_LOG_LIMIT: usize = 100;
+
+/// A user-defined selection policy, kept as source text so it round-trips through storage
+/// exactly as typed.
+///
+/// Each non-empty line is one rule, `#` starts a comment:
+///
+/// ```text
+/// exclude name contains "trial"
+/// prefer metadata region = "JP" max 300ms
+/// prefer any
+/// ```
+///
+/// `exclude` rules remove every matching outbound from consideration. `prefer` rules are tried
+/// in order; the first one with an alive candidate (under its `max` latency when given) wins and
+/// its fastest candidate is selected. Conditions are `any`, `name contains "text"`,
+/// `tag prefix "text"`, `subscription "name"` and `metadata key = "value"`, joined with `and`.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct NamedPolicy {
+    pub name: String,
+    pub source: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct PolicyEngineSettings {
+    pub enabled: bool,
+    pub dry_run: bool,
+    pub active_policy: String,
+    pub policies: Vec<NamedPolicy>,
+}
+
+impl Default for PolicyEngineSettings {
+    fn default() -> Self {
+        PolicyEngineSettings {
+            enabled: false,
+            dry_run: true,
+            active_policy: "default".to_string(),
+            policies: vec![NamedPolicy {
+                name: "default".to_string(),
+                source: "exclude name contains \"trial\"\nprefer any\n".to_string(),
+            }],
+        }
+    }
+}
+
+impl PolicyEngineSettings {
+    pub fn active(&self) -> Option<&NamedPolicy> {
+        self.policies
+            .iter()
+            .find(|policy| policy.name == self.active_policy)
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum PolicyCondition {
+    Any,
+    NameContains(String),
+    TagPrefix(String),
+    Subscription(String),
+    Metadata(String, String),
+}
+
+impl PolicyCondition {
+    fn matches(&self, candidate: &PolicyCandidate) -> bool {
+        match self {
+            PolicyCondition::Any => true,
+            PolicyCondition::NameContains(text) => candidate
+                .display_name
+                .to_lowercase()
+                .contains(&text.to_lowercase()),
+            PolicyCondition::TagPrefix(prefix) => candidate.outbound_tag.starts_with(prefix),
+            PolicyCondition::Subscription(name) => {
+                candidate.subscription_name.as_deref() == Some(name.as_str())
+            }
+            PolicyCondition::Metadata(key, value) => candidate
+                .metadata
+                .get(key)
+                .is_some_and(|actual| actual.eq_ignore_ascii_case(value)),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            PolicyCondition::Any => "any".to_string(),
+            PolicyCondition::NameContains(text) => format!("name contains \"{}\"", text),
+            PolicyCondition::TagPrefix(prefix) => format!("tag prefix \"{}\"", prefix),
+            PolicyCondition::Subscription(name) => format!("subscription \"{}\"", name),
+            PolicyCondition::Metadata(key, value) => format!("metadata {} = \"{}\"", key, value),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum PolicyRule {
+    Exclude(Vec<PolicyCondition>),
+    Prefer {
+        conditions: Vec<PolicyCondition>,
+        max_latency_ms: Option<i64>,
+    },
+}
+
+fn describe_conditions(conditions: &[PolicyCondition]) -> String {
+    conditions
+        .iter()
+        .map(|condition| condition.describe())
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+impl PolicyRule {
+    pub fn describe(&self) -> String {
+        match self {
+            PolicyRule::Exclude(conditions) => {
+                format!("exclude {}", describe_conditions(conditions))
+            }
+            PolicyRule::Prefer {
+                conditions,
+                max_latency_ms: Some(max_latency_ms),
+            } => format!(
+                "prefer {} max {}ms",
+                describe_conditions(conditions),
+                max_latency_ms
+            ),
+            PolicyRule::Prefer {
+                conditions,
+                max_latency_ms: None,
+            } => format!("prefer {}", describe_conditions(conditions)),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct PolicyParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = vec![];
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.peek().copied() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => token.push(c),
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+            tokens.push(token);
+        } else if c == '=' {
+            chars.next();
+            tokens.push("=".to_string());
+        } else {
+            let mut token = String::new();
+            while let Some(c) = chars.peek().copied() {
+                if c.is_whitespace() || c == '"' || c == '=' {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+fn expect_value(tokens: &[String], index: &mut usize, what: &str) -> Result<String, String> {
+    let value = tokens
+        .get(*index)
+        .cloned()
+        .ok_or_else(|| format!("expected {}", what))?;
+    *index += 1;
+    Ok(value)
+}
+
+fn parse_condition(tokens: &[String], index: &mut usize) -> Result<PolicyCondition, String> {
+    let keyword = expect_value(tokens, index, "a condition")?;
+    match keyword.as_str() {
+        "any" => Ok(PolicyCondition::Any),
+        "name" => {
+            if expect_value(tokens, index, "\"contains\"")? != "contains" {
+                return Err("expected \"contains\" after \"name\"".to_string());
+            }
+            Ok(PolicyCondition::NameContains(expect_value(
+                tokens, index, "a name",
+            )?))
+        }
+        "tag" => {
+            if expect_value(tokens, index, "\"prefix\"")? != "prefix" {
+                return Err("expected \"prefix\" after \"tag\"".to_string());
+            }
+            Ok(PolicyCondition::TagPrefix(expect_value(
+                tokens,
+                index,
+                "a tag prefix",
+            )?))
+        }
+        "subscription" => Ok(PolicyCondition::Subscription(expect_value(
+            tokens,
+            index,
+            "a subscription name",
+        )?)),
+        "metadata" => {
+            let key = expect_value(tokens, index, "a metadata key")?;
+            if expect_value(tokens, index, "\"=\"")? != "=" {
+                return Err(format!("expected \"=\" after metadata key {}", key));
+            }
+            let value = expect_value(tokens, index, "a metadata value")?;
+            Ok(PolicyCondition::Metadata(key, value))
+        }
+        other => Err(format!("unknown condition \"{}\"", other)),
+    }
+}
+
+fn parse_latency(token: &str) -> Result<i64, String> {
+    token
+        .trim_end_matches("ms")
+        .parse::<i64>()
+        .map_err(|_| format!("invalid latency \"{}\"", token))
+}
+
+fn parse_rule(line: &str) -> Result<PolicyRule, String> {
+    let tokens = tokenize(line)?;
+    let mut index = 0;
+    let verb = expect_value(&tokens, &mut index, "\"exclude\" or \"prefer\"")?;
+    let mut conditions = vec![parse_condition(&tokens, &mut index)?];
+    let mut max_latency_ms = None;
+    while index < tokens.len() {
+        match tokens[index].as_str() {
+            "and" => {
+                index += 1;
+                conditions.push(parse_condition(&tokens, &mut index)?);
+            }
+            "max" if verb == "prefer" => {
+                index += 1;
+                max_latency_ms = Some(parse_latency(&expect_value(
+                    &tokens,
+                    &mut index,
+                    "a latency",
+                )?)?);
+            }
+            other => return Err(format!("unexpected \"{}\"", other)),
+        }
+    }
+    match verb.as_str() {
+        "exclude" => Ok(PolicyRule::Exclude(conditions)),
+        "prefer" => Ok(PolicyRule::Prefer {
+            conditions,
+            max_latency_ms,
+        }),
+        other => Err(format!(
+            "unknown rule \"{}\", expected \"exclude\" or \"prefer\"",
+            other
+        )),
+    }
+}
+
+pub fn parse_policy(source: &str) -> Result<Vec<PolicyRule>, PolicyParseError> {
+    let mut rules = vec![];
+    for (line_index, line) in source.lines().enumerate() {
+        let line = match line.find('#') {
+            Some(comment) => &line[..comment],
+            None => line,
+        }
+        .trim();
+        if line.is_empty() {
+            continue;
+        }
+        let rule = parse_rule(line).map_err(|message| PolicyParseError {
+            line: line_index + 1,
+            message,
+        })?;
+        rules.push(rule);
+    }
+    Ok(rules)
+}
+
+/// An outbound as seen by the policy engine.
+#[derive(PartialEq, Debug, Clone)]
+pub struct PolicyCandidate {
+    pub outbound_tag: String,
+    pub display_name: String,
+    pub subscription_name: Option<String>,
+    pub metadata: HashMap<String, String>,
+    pub alive: bool,
+    pub delay: i64,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct PolicyDecision {
+    pub winner: Option<String>,
+    pub explanation: Vec<String>,
+}
+
+pub fn evaluate_policy(rules: &[PolicyRule], candidates: &[PolicyCandidate]) -> PolicyDecision {
+    let mut explanation = vec![];
+    let mut remaining: Vec<&PolicyCandidate> = candidates.iter().collect();
+    for rule in rules {
+        if let PolicyRule::Exclude(conditions) = rule {
+            let before = remaining.len();
+            remaining.retain(|candidate| {
+                !conditions
+                    .iter()
+                    .all(|condition| condition.matches(candidate))
+            });
+            explanation.push(format!(
+                "{}: removed {} of {} outbounds",
+                rule.describe(),
+                before - remaining.len(),
+                before
+            ));
+        }
+    }
+
+    for rule in rules {
+        let PolicyRule::Prefer {
+            conditions,
+            max_latency_ms,
+        } = rule
+        else {
+            continue;
+        };
+        let matching: Vec<&&PolicyCandidate> = remaining
+            .iter()
+            .filter(|candidate| {
+                conditions
+                    .iter()
+                    .all(|condition| condition.matches(candidate))
+            })
+            .collect();
+        let eligible = matching.iter().filter(|candidate| {
+            candidate.alive && max_latency_ms.map_or(true, |max| candidate.delay <= max)
+        });
+        match eligible.min_by_key(|candidate| candidate.delay) {
+            Some(winner) => {
+                explanation.push(format!(
+                    "{}: {} matching, picked {} ({} ms)",
+                    rule.describe(),
+                    matching.len(),
+                    winner.display_name,
+                    winner.delay
+                ));
+                return PolicyDecision {
+                    winner: Some(winner.outbound_tag.clone()),
+                    explanation,
+                };
+            }
+            None => explanation.push(format!(
+                "{}: {} matching, none alive{}",
+                rule.describe(),
+                matching.len(),
+                match max_latency_ms {
+                    Some(max) => format!(" under {} ms", max),
+                    None => "".to_string(),
+                }
+            )),
+        }
+    }
+
+    explanation.push("No rule selected an outbound, the balancer is left unchanged".to_string());
+    PolicyDecision {
+        winner: None,
+        explanation,
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct PolicyLogEntry {
+    pub at: i64,
+    pub policy_name: String,
+    pub decision: PolicyDecision,
+    pub applied: bool,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct PolicyLog {
+    pub latest: Option<PolicyLogEntry>,
+    pub entries: Vec<PolicyLogEntry>,
+}
+
+impl PolicyLog {
+    pub fn new() -> PolicyLog {
+        PolicyLog {
+            latest: None,
+            entries: vec![],
+        }
+    }
+
+    /// Keeps the latest evaluation and appends it to the history when the winner changes or the
+    /// decision was applied, so latency jitter alone does not flood the log.
+    pub fn record(&mut self, entry: PolicyLogEntry) {
+        let repeats_last = self.entries.last().is_some_and(|last| {
+            last.policy_name == entry.policy_name && last.decision.winner == entry.decision.winner
+        });
+        if entry.applied || !repeats_last {
+            self.entries.push(entry.clone());
+            if self.entries.len() > POLICY_LOG_LIMIT {
+                self.entries.remove(0);
+            }
+        }
+        self.latest = Some(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(
+        outbound_tag: &str,
+        display_name: &str,
+        alive: bool,
+        delay: i64,
+    ) -> PolicyCandidate {
+        PolicyCandidate {
+            outbound_tag: outbound_tag.to_string(),
+            display_name: display_name.to_string(),
+            subscription_name: Some("provider".to_string()),
+            metadata: HashMap::from([("region".to_string(), "JP".to_string())]),
+            alive,
+            delay,
+        }
+    }
+
+    #[test]
+    fn parses_rules_and_skips_comments() {
+        let rules = parse_policy(
+            "# comment\nexclude name contains \"trial\"\n\nprefer metadata region = \"JP\" and tag prefix \"sub_\" max 300ms # fast\nprefer any\n",
+        )
+        .unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                PolicyRule::Exclude(vec![PolicyCondition::NameContains("trial".to_string())]),
+                PolicyRule::Prefer {
+                    conditions: vec![
+                        PolicyCondition::Metadata("region".to_string(), "JP".to_string()),
+                        PolicyCondition::TagPrefix("sub_".to_string()),
+                    ],
+                    max_latency_ms: Some(300),
+                },
+                PolicyRule::Prefer {
+                    conditions: vec![PolicyCondition::Any],
+                    max_latency_ms: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_the_line_of_a_parse_error() {
+        let error = parse_policy("prefer any\nprefer name is \"x\"\n").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.message, "expected \"contains\" after \"name\"");
+        assert!(parse_policy("prefer name contains \"x").is_err());
+        assert!(parse_policy("exclude any max 100").is_err());
+        assert!(parse_policy("pick any").is_err());
+    }
+
+    #[test]
+    fn describe_round_trips_through_the_parser() {
+        let source = "exclude name contains \"trial\" and subscription \"provider\"\nprefer metadata region = \"JP\" max 300ms\n";
+        let rules = parse_policy(source).unwrap();
+        let described: Vec<String> = rules.iter().map(PolicyRule::describe).collect();
+        assert_eq!(parse_policy(&described.join("\n")).unwrap(), rules);
+    }
+
+    #[test]
+    fn picks_the_fastest_alive_candidate_of_the_first_matching_rule() {
+        let rules = parse_policy(
+            "exclude name contains \"trial\"\nprefer name contains \"premium\" max 100ms\nprefer any\n",
+        )
+        .unwrap();
+        let candidates = vec![
+            candidate("a", "Trial 1", true, 10),
+            candidate("b", "Premium 1", true, 150),
+            candidate("c", "Premium 2", false, 20),
+            candidate("d", "Basic 1", true, 80),
+            candidate("e", "Basic 2", true, 60),
+        ];
+        let decision = evaluate_policy(&rules, &candidates);
+        assert_eq!(decision.winner.as_deref(), Some("e"));
+        assert_eq!(
+            decision.explanation[1],
+            "prefer name contains \"premium\" max 100ms: 2 matching, none alive under 100 ms"
+        );
+    }
+
+    #[test]
+    fn leaves_the_balancer_alone_without_a_winner() {
+        let rules = parse_policy("prefer subscription \"other\"").unwrap();
+        let decision = evaluate_policy(&rules, &[candidate("a", "A", true, 10)]);
+        assert_eq!(decision.winner, None);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::client_status::alerts::AlertSettings;
+use crate::client_status::manual_override::{OverrideExpiry, OverrideRecord};
+use crate::client_status::notifications::NotificationSettings;
+use crate::client_status::policy::PolicyEngineSettings;
+use crate::client_status::schedule::ScheduleSettings;
+use crate::client_status::storage::VersionedStore;
+use crate::client_status::subscription_refresh::SubscriptionRefreshSettings;
+use crate::client_status::watchdog::WatchdogSettings;
+use crate::grpc::proto::v2ray::core::app::subscription;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Version 0 is the unversioned object saved before the store was versioned. Version 1 only adds
+/// fields, which `#[serde(default)]` fills in, so the data carries over unchanged.
+pub const PREFERENCES_STORE: VersionedStore = VersionedStore {
+    key: "rendezvous.preferences",
+    migrations: &[migrate_unversioned_preferences],
+};
+
+fn migrate_unversioned_preferences(data: Value) -> Value {
+    data
+}
+
+/// How often the background worker polls the core, in milliseconds.
+pub const REFRESH_INTERVAL_CHOICES_MS: [u32; 5] = [1000, 2000, 5000, 10000, 30000];
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum Theme {
+    Auto,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Auto, Theme::Light, Theme::Dark];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Auto => "auto",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Theme> {
+        Theme::ALL.iter().find(|theme| theme.name() == name).copied()
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum LatencyClass {
+    Fast,
+    Moderate,
+    Slow,
+}
+
+/// Delays up to `fast_ms` count as fast and delays above `slow_ms` as slow.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct LatencyThresholds {
+    pub fast_ms: i64,
+    pub slow_ms: i64,
+}
+
+impl Default for LatencyThresholds {
+    fn default() -> Self {
+        LatencyThresholds {
+            fast_ms: 300,
+            slow_ms: 1000,
+        }
+    }
+}
+
+impl LatencyThresholds {
+    pub fn classify(&self, delay_ms: i64) -> LatencyClass {
+        if delay_ms <= self.fast_ms {
+            LatencyClass::Fast
+        } else if delay_ms <= self.slow_ms {
+            LatencyClass::Moderate
+        } else {
+            LatencyClass::Slow
+        }
+    }
+}
+
+/// The `ImportSource` fields the add-subscription form does not ask for.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct ImportSourceDefaults {
+    pub import_using_tag: String,
+    pub default_expire_seconds: u64,
+}
+
+impl Default for ImportSourceDefaults {
+    fn default() -> Self {
+        ImportSourceDefaults {
+            import_using_tag: "direct".to_string(),
+            default_expire_seconds: 3600,
+        }
+    }
+}
+
+impl ImportSourceDefaults {
+    pub fn import_source(&self, name: &str, url: &str) -> subscription::ImportSource {
+        subscription::ImportSource {
+            name: name.to_string(),
+            url: url.to_string(),
+            tag_prefix: format!("subscription_{}", name),
+            import_using_tag: self.import_using_tag.clone(),
+            default_expire_seconds: self.default_expire_seconds,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum PresetColor {
+    Primary,
+    Secondary,
+    Success,
+    Info,
+    Warning,
+    Danger,
+    Dark,
+}
+
+impl PresetColor {
+    pub const ALL: [PresetColor; 7] = [
+        PresetColor::Primary,
+        PresetColor::Secondary,
+        PresetColor::Success,
+        PresetColor::Info,
+        PresetColor::Warning,
+        PresetColor::Danger,
+        PresetColor::Dark,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PresetColor::Primary => "primary",
+            PresetColor::Secondary => "secondary",
+            PresetColor::Success => "success",
+            PresetColor::Info => "info",
+            PresetColor::Warning => "warning",
+            PresetColor::Danger => "danger",
+            PresetColor::Dark => "dark",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<PresetColor> {
+        PresetColor::ALL
+            .iter()
+            .find(|color| color.name() == name)
+            .copied()
+    }
+}
+
+/// An operational mode that pins the balancer override to a fixed outbound tag.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ModePreset {
+    pub label: String,
+    pub target: String,
+    pub color: PresetColor,
+}
+
+pub fn default_mode_presets() -> Vec<ModePreset> {
+    vec![
+        ModePreset {
+            label: "Direct".to_string(),
+            target: "direct".to_string(),
+            color: PresetColor::Secondary,
+        },
+        ModePreset {
+            label: "Blackhole".to_string(),
+            target: "deny".to_string(),
+            color: PresetColor::Dark,
+        },
+    ]
+}
+
+/// A starred server, keyed by outbound tag. The display name is kept so a favorite the provider
+/// stopped serving can still be recognized.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct FavoriteServer {
+    pub outbound_tag: String,
+    pub display_name: String,
+}
+
+/// An outbound installed from a share link. The core has no way to list them back, so the link
+/// is kept to show them and re-add them after the core restarts.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ManualOutbound {
+    pub outbound_tag: String,
+    pub share_link: String,
+    pub added_at: i64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct Preferences {
+    pub mode_presets: Vec<ModePreset>,
+    pub override_expiry: Option<OverrideExpiry>,
+    pub override_origin: Option<OverrideRecord>,
+    pub watchdog: WatchdogSettings,
+    pub policy_engine: PolicyEngineSettings,
+    pub schedule: ScheduleSettings,
+    pub favorites: Vec<FavoriteServer>,
+    pub manual_outbounds: Vec<ManualOutbound>,
+    pub subscription_refresh: SubscriptionRefreshSettings,
+    /// Whether the background worker fetches stale subscription documents on its own. Off by
+    /// default, as the browser sends the subscription URL, token included, to the provider.
+    pub fetch_documents_automatically: bool,
+    pub refresh_interval_ms: u32,
+    pub theme: Theme,
+    pub latency_thresholds: LatencyThresholds,
+    pub import_defaults: ImportSourceDefaults,
+    pub notifications: NotificationSettings,
+    pub alerts: AlertSettings,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences {
+            mode_presets: default_mode_presets(),
+            override_expiry: None,
+            override_origin: None,
+            watchdog: WatchdogSettings::default(),
+            policy_engine: PolicyEngineSettings::default(),
+            schedule: ScheduleSettings::default(),
+            favorites: vec![],
+            manual_outbounds: vec![],
+            subscription_refresh: SubscriptionRefreshSettings::default(),
+            fetch_documents_automatically: false,
+            refresh_interval_ms: 1000,
+            theme: Theme::Auto,
+            latency_thresholds: LatencyThresholds::default(),
+            import_defaults: ImportSourceDefaults::default(),
+            notifications: NotificationSettings::default(),
+            alerts: AlertSettings::default(),
+        }
+    }
+}
+
+impl Preferences {
+    pub fn load() -> Preferences {
+        PREFERENCES_STORE.load().unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        PREFERENCES_STORE.save(self);
+    }
+
+    pub fn is_favorite(&self, outbound_tag: &str) -> bool {
+        self.favorites
+            .iter()
+            .any(|favorite| favorite.outbound_tag == outbound_tag)
+    }
+
+    /// Stars the server when it is not a favorite yet, otherwise removes the star.
+    pub fn toggle_favorite(&self, outbound_tag: &str, display_name: &str) -> Preferences {
+        let mut favorites = self.favorites.clone();
+        if self.is_favorite(outbound_tag) {
+            favorites.retain(|favorite| favorite.outbound_tag != outbound_tag);
+        } else {
+            favorites.push(FavoriteServer {
+                outbound_tag: outbound_tag.to_string(),
+                display_name: display_name.to_string(),
+            });
+        }
+        Preferences {
+            favorites,
+            ..self.clone()
+        }
+    }
+
+    /// Records an added outbound, replacing an earlier one with the same tag.
+    pub fn with_manual_outbound(&self, manual_outbound: ManualOutbound) -> Preferences {
+        let mut manual_outbounds = self
+            .without_manual_outbound(&manual_outbound.outbound_tag)
+            .manual_outbounds;
+        manual_outbounds.push(manual_outbound);
+        Preferences {
+            manual_outbounds,
+            ..self.clone()
+        }
+    }
+
+    pub fn without_manual_outbound(&self, outbound_tag: &str) -> Preferences {
+        let mut manual_outbounds = self.manual_outbounds.clone();
+        manual_outbounds.retain(|manual_outbound| manual_outbound.outbound_tag != outbound_tag);
+        Preferences {
+            manual_outbounds,
+            ..self.clone()
+        }
+    }
+
+    pub fn preset_for_target(&self, target: &str) -> Option<&ModePreset> {
+        self.mode_presets
+            .iter()
+            .find(|preset| preset.target == target)
+    }
+}
@@ -0,0 +1,102 @@
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+
+/// The page shown by `PrimaryUI`, kept in the URL hash so pages, an expanded subscription and
+/// an expanded server can be bookmarked and walked through with back and forward.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub enum Route {
+    #[default]
+    Dashboard,
+    Subscriptions,
+    /// The subscription list with this subscription's accordion expanded.
+    Subscription(String),
+    /// The subscription list with the details of the server with this outbound tag expanded.
+    Server(String),
+    Observatory,
+    Balancer,
+    Timeline,
+    Alerts,
+    Policy,
+    Setting,
+}
+
+fn encode_segment(segment: &str) -> String {
+    utf8_percent_encode(segment, NON_ALPHANUMERIC).to_string()
+}
+
+fn decode_segment(segment: &str) -> Option<String> {
+    let decoded = percent_decode_str(segment).decode_utf8().ok()?;
+    if decoded.is_empty() {
+        return None;
+    }
+    Some(decoded.to_string())
+}
+
+impl Route {
+    /// Parses a location hash such as `#/subscriptions/name`. Unknown hashes fall back to the
+    /// default page.
+    pub fn from_hash(hash: &str) -> Route {
+        let path = hash.trim_start_matches('#').trim_start_matches('/');
+        let mut segments = path.splitn(2, '/');
+        let page = segments.next().unwrap_or_default();
+        let argument = segments.next().and_then(decode_segment);
+        match (page, argument) {
+            ("dashboard", _) => Route::Dashboard,
+            ("subscriptions", None) => Route::Subscriptions,
+            ("subscriptions", Some(name)) => Route::Subscription(name),
+            ("servers", Some(outbound_tag)) => Route::Server(outbound_tag),
+            ("observatory", _) => Route::Observatory,
+            ("balancer", _) => Route::Balancer,
+            ("timeline", _) => Route::Timeline,
+            ("alerts", _) => Route::Alerts,
+            ("policy", _) => Route::Policy,
+            ("settings", _) => Route::Setting,
+            _ => Route::default(),
+        }
+    }
+
+    pub fn to_hash(&self) -> String {
+        match self {
+            Route::Dashboard => "#/dashboard".to_string(),
+            Route::Subscriptions => "#/subscriptions".to_string(),
+            Route::Subscription(name) => format!("#/subscriptions/{}", encode_segment(name)),
+            Route::Server(outbound_tag) => format!("#/servers/{}", encode_segment(outbound_tag)),
+            Route::Observatory => "#/observatory".to_string(),
+            Route::Balancer => "#/balancer".to_string(),
+            Route::Timeline => "#/timeline".to_string(),
+            Route::Alerts => "#/alerts".to_string(),
+            Route::Policy => "#/policy".to_string(),
+            Route::Setting => "#/settings".to_string(),
+        }
+    }
+
+    /// The top level page this route belongs to, used to pick the page and the active nav item.
+    pub fn page(&self) -> Route {
+        match self {
+            Route::Subscription(_) | Route::Server(_) => Route::Subscriptions,
+            route => route.clone(),
+        }
+    }
+
+    /// The route for the current location of the page.
+    pub fn current() -> Route {
+        Route::from_location().unwrap_or_default()
+    }
+
+    /// The route in the location hash, if the location has one.
+    pub fn from_location() -> Option<Route> {
+        let hash = web_sys::window().and_then(|window| window.location().hash().ok())?;
+        if hash.trim_start_matches('#').is_empty() {
+            return None;
+        }
+        Some(Route::from_hash(&hash))
+    }
+
+    /// Moves the browser to this route. This adds a history entry, and the `hashchange` listener
+    /// installed by `App` then updates `UIStatus.route`.
+    pub fn navigate(&self) {
+        if let Some(window) = web_sys::window() {
+            let _ = window.location().set_hash(&self.to_hash());
+        }
+    }
+}
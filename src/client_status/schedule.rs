@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const MINUTES_PER_WEEK: u32 = 7 * MINUTES_PER_DAY;
+const SCHEDULE_EVENT_LIMIT: usize = 50;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "Mon",
+            Weekday::Tuesday => "Tue",
+            Weekday::Wednesday => "Wed",
+            Weekday::Thursday => "Thu",
+            Weekday::Friday => "Fri",
+            Weekday::Saturday => "Sat",
+            Weekday::Sunday => "Sun",
+        }
+    }
+
+    fn index(&self) -> u32 {
+        Weekday::ALL.iter().position(|day| day == self).unwrap_or(0) as u32
+    }
+}
+
+/// Formats minutes since midnight as `HH:MM`, the value format of `<input type="time">`.
+pub fn format_minute_of_day(minute: u32) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+pub fn parse_minute_of_day(value: &str) -> Option<u32> {
+    let (hours, minutes) = value.split_once(':')?;
+    let hours = hours.parse::<u32>().ok()?;
+    let minutes = minutes.get(..2).unwrap_or(minutes).parse::<u32>().ok()?;
+    if hours >= 24 || minutes >= 60 {
+        return None;
+    }
+    Some(hours * 60 + minutes)
+}
+
+/// A weekly time range during which the balancer override is pinned to `target`.
+///
+/// The range begins at `start_minute` on each of `days`; when `end_minute` is not after
+/// `start_minute` it runs past midnight into the following day. An empty `target` means
+/// automatic selection, the same as "Running".
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ScheduleRange {
+    pub days: Vec<Weekday>,
+    pub start_minute: u32,
+    pub end_minute: u32,
+    pub target: String,
+}
+
+impl ScheduleRange {
+    fn covers(&self, minute_of_week: u32) -> bool {
+        let length = if self.end_minute > self.start_minute {
+            self.end_minute - self.start_minute
+        } else {
+            self.end_minute + MINUTES_PER_DAY - self.start_minute
+        };
+        self.days.iter().any(|day| {
+            let start = day.index() * MINUTES_PER_DAY + self.start_minute;
+            (minute_of_week + MINUTES_PER_WEEK - start) % MINUTES_PER_WEEK < length
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ScheduleSettings {
+    pub enabled: bool,
+    pub ranges: Vec<ScheduleRange>,
+}
+
+impl ScheduleSettings {
+    /// The target scheduled at `minute_of_week`, `None` when no range covers it.
+    /// Earlier ranges win where ranges overlap.
+    pub fn target_at(&self, minute_of_week: u32) -> Option<&str> {
+        self.ranges
+            .iter()
+            .find(|range| range.covers(minute_of_week % MINUTES_PER_WEEK))
+            .map(|range| range.target.as_str())
+    }
+
+    /// Finds the next minute at which the scheduled target changes, returning the number of
+    /// minutes until then and the target that takes over.
+    pub fn next_change(&self, minute_of_week: u32) -> Option<(u32, Option<String>)> {
+        let current = self.target_at(minute_of_week);
+        (1..=MINUTES_PER_WEEK).find_map(|offset| {
+            let target = self.target_at(minute_of_week + offset);
+            if target != current {
+                Some((offset, target.map(|target| target.to_string())))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// The current local time as minutes since Monday 00:00.
+pub fn local_minute_of_week() -> u32 {
+    let date = js_sys::Date::new_0();
+    let weekday_from_monday = (date.get_day() + 6) % 7;
+    weekday_from_monday * MINUTES_PER_DAY + date.get_hours() * 60 + date.get_minutes()
+}
+
+pub fn format_minute_of_week(minute_of_week: u32) -> String {
+    let minute_of_week = minute_of_week % MINUTES_PER_WEEK;
+    format!(
+        "{} {}",
+        Weekday::ALL[(minute_of_week / MINUTES_PER_DAY) as usize].short_name(),
+        format_minute_of_day(minute_of_week % MINUTES_PER_DAY)
+    )
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct ScheduleEvent {
+    pub at: i64,
+    pub target: String,
+}
+
+/// Remembers when the schedule was last checked so a target is applied once, when its range
+/// begins, rather than on every poll.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ScheduleState {
+    pub last_checked_minute: Option<u32>,
+    pub events: Vec<ScheduleEvent>,
+}
+
+impl ScheduleState {
+    pub fn new() -> ScheduleState {
+        ScheduleState {
+            last_checked_minute: None,
+            events: vec![],
+        }
+    }
+
+    /// Returns the target to apply when a range boundary was crossed since the last check.
+    /// Leaving every range returns `Some("")`, handing selection back to the balancer.
+    pub fn observe(&mut self, settings: &ScheduleSettings, minute_of_week: u32) -> Option<String> {
+        let last_checked_minute = self.last_checked_minute.replace(minute_of_week);
+        if !settings.enabled {
+            return None;
+        }
+        let last_checked_minute = last_checked_minute?;
+        if last_checked_minute == minute_of_week {
+            return None;
+        }
+        let previous = settings.target_at(last_checked_minute);
+        let current = settings.target_at(minute_of_week);
+        if previous == current {
+            return None;
+        }
+        Some(current.unwrap_or("").to_string())
+    }
+
+    pub fn record(&mut self, event: ScheduleEvent) {
+        self.events.push(event);
+        if self.events.len() > SCHEDULE_EVENT_LIMIT {
+            self.events.remove(0);
+        }
+    }
+}
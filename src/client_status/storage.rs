@@ -0,0 +1,122 @@
+use gloo_console::log;
+use gloo_storage::{LocalStorage, Storage};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use wasm_bindgen::JsValue;
+
+thread_local! {
+    /// Keys whose stored value could not be loaded in this session. They are not saved over, so
+    /// a value written by a newer release or damaged by hand is not replaced with defaults.
+    static UNREADABLE_KEYS: RefCell<BTreeSet<&'static str>> =
+        const { RefCell::new(BTreeSet::new()) };
+}
+
+/// Upgrades stored data by one version.
+pub type Migration = fn(Value) -> Value;
+
+/// A value kept in localStorage as `{"version": n, "data": ...}`. `migrations[i]` turns data
+/// written at version `i` into version `i + 1`, so the current version is the number of
+/// migrations. A value without the envelope predates versioning and is treated as version 0.
+pub struct VersionedStore {
+    pub key: &'static str,
+    pub migrations: &'static [Migration],
+}
+
+impl VersionedStore {
+    pub fn version(&self) -> usize {
+        self.migrations.len()
+    }
+
+    /// Where the stored value is copied to when it cannot be loaded.
+    pub fn backup_key(&self) -> String {
+        format!("{}.unreadable", self.key)
+    }
+
+    /// Whether the stored value could not be loaded, so saving is held back.
+    pub fn is_unreadable(&self) -> bool {
+        UNREADABLE_KEYS.with(|keys| keys.borrow().contains(self.key))
+    }
+
+    /// Allows saving over the unreadable value again. The copy under `backup_key` is kept.
+    pub fn discard_unreadable(&self) {
+        UNREADABLE_KEYS.with(|keys| keys.borrow_mut().remove(self.key));
+    }
+
+    fn keep_unreadable(&self, stored: &Value) {
+        UNREADABLE_KEYS.with(|keys| keys.borrow_mut().insert(self.key));
+        if let Err(e) = LocalStorage::set(self.backup_key(), stored) {
+            log!(<std::string::String as Into<JsValue>>::into(format!(
+                "Failed to back up {}: {:?}",
+                self.key, e
+            )));
+        }
+    }
+
+    pub fn load<T: DeserializeOwned>(&self) -> Option<T> {
+        let stored: Value = LocalStorage::get(self.key).ok()?;
+        let (version, mut data) = match stored.clone() {
+            Value::Object(mut envelope)
+                if envelope.len() == 2
+                    && envelope.contains_key("data")
+                    && envelope.get("version").is_some_and(Value::is_u64) =>
+            {
+                let version = envelope["version"].as_u64().unwrap_or_default() as usize;
+                (version, envelope.remove("data").unwrap_or_default())
+            }
+            data => (0, data),
+        };
+        if version > self.version() {
+            log!(<std::string::String as Into<JsValue>>::into(format!(
+                "Ignoring {} stored by a newer version ({} > {})",
+                self.key,
+                version,
+                self.version()
+            )));
+            self.keep_unreadable(&stored);
+            return None;
+        }
+        for migration in &self.migrations[version..] {
+            data = migration(data);
+        }
+        match serde_json::from_value(data) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                log!(<std::string::String as Into<JsValue>>::into(format!(
+                    "Failed to load {}: {}",
+                    self.key, e
+                )));
+                self.keep_unreadable(&stored);
+                None
+            }
+        }
+    }
+
+    pub fn save<T: Serialize>(&self, value: &T) {
+        if self.is_unreadable() {
+            log!(<std::string::String as Into<JsValue>>::into(format!(
+                "Not saving {} over a value that could not be loaded",
+                self.key
+            )));
+            return;
+        }
+        let stored = match serde_json::to_value(value) {
+            Ok(data) => json!({ "version": self.version(), "data": data }),
+            Err(e) => {
+                log!(<std::string::String as Into<JsValue>>::into(format!(
+                    "Failed to serialize {}: {}",
+                    self.key, e
+                )));
+                return;
+            }
+        };
+        if let Err(e) = LocalStorage::set(self.key, stored) {
+            log!(<std::string::String as Into<JsValue>>::into(format!(
+                "Failed to save {}: {:?}",
+                self.key, e
+            )));
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use crate::client_status::core_link::FetchedSubscription;
+use crate::grpc::proto::v2ray::core::app::subscription::ImportSource;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const BACKUP_FORMAT: &str = "rendezvous-subscriptions";
+/// Bump when the file layout changes, and teach `SubscriptionBackup::parse` to read the old one.
+pub const BACKUP_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct BackupImportSource {
+    pub name: String,
+    pub url: String,
+    pub tag_prefix: String,
+    pub import_using_tag: String,
+    pub default_expire_seconds: u64,
+}
+
+impl BackupImportSource {
+    pub fn from_import_source(source: &ImportSource) -> BackupImportSource {
+        BackupImportSource {
+            name: source.name.clone(),
+            url: source.url.clone(),
+            tag_prefix: source.tag_prefix.clone(),
+            import_using_tag: source.import_using_tag.clone(),
+            default_expire_seconds: source.default_expire_seconds,
+        }
+    }
+
+    pub fn to_import_source(&self) -> ImportSource {
+        ImportSource {
+            name: self.name.clone(),
+            url: self.url.clone(),
+            tag_prefix: self.tag_prefix.clone(),
+            import_using_tag: self.import_using_tag.clone(),
+            default_expire_seconds: self.default_expire_seconds,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SubscriptionBackup {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: i64,
+    pub subscriptions: Vec<BackupImportSource>,
+}
+
+/// Only the version is read first, so files written by a newer release get a clear error.
+#[derive(Deserialize)]
+struct BackupHeader {
+    format: String,
+    version: u32,
+}
+
+impl SubscriptionBackup {
+    /// Collects the import source of every subscription whose status has been fetched.
+    pub fn from_fetched(
+        fetched_subscription: &FetchedSubscription,
+        now: i64,
+    ) -> SubscriptionBackup {
+        SubscriptionBackup {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            exported_at: now,
+            subscriptions: fetched_subscription
+                .managed
+                .values()
+                .flatten()
+                .filter_map(|status| status.import_source.as_ref())
+                .map(BackupImportSource::from_import_source)
+                .collect(),
+        }
+    }
+
+    /// Subscriptions `from_fetched` leaves out, as their status or import source has not been
+    /// fetched.
+    pub fn unexported_names(fetched_subscription: &FetchedSubscription) -> Vec<String> {
+        fetched_subscription
+            .managed
+            .iter()
+            .filter(|(_, status)| {
+                status
+                    .as_ref()
+                    .and_then(|status| status.import_source.as_ref())
+                    .is_none()
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn parse(text: &str) -> Result<SubscriptionBackup, String> {
+        let header: BackupHeader =
+            serde_json::from_str(text).map_err(|e| format!("not a backup file: {}", e))?;
+        if header.format != BACKUP_FORMAT {
+            return Err(format!("unknown backup format \"{}\"", header.format));
+        }
+        match header.version {
+            BACKUP_VERSION => serde_json::from_str(text).map_err(|e| e.to_string()),
+            version => Err(format!(
+                "backup version {} is newer than the supported version {}",
+                version, BACKUP_VERSION
+            )),
+        }
+    }
+}
+
+/// What restoring a backup would change in the tracked subscriptions.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct BackupDiff {
+    pub added: Vec<BackupImportSource>,
+    pub changed: Vec<(BackupImportSource, BackupImportSource)>,
+    pub removed: Vec<String>,
+    /// Subscriptions defined in the core configuration that differ from or are missing in the
+    /// backup; the API cannot replace or remove them.
+    pub kept: Vec<String>,
+    pub unchanged: usize,
+}
+
+impl BackupDiff {
+    pub fn new(
+        fetched_subscription: &FetchedSubscription,
+        backup: &SubscriptionBackup,
+    ) -> BackupDiff {
+        let current: BTreeMap<&String, _> = fetched_subscription
+            .managed
+            .iter()
+            .filter_map(|(name, status)| Some((name, status.as_ref()?)))
+            .collect();
+        let mut diff = BackupDiff::default();
+        for source in &backup.subscriptions {
+            let Some(status) = current.get(&source.name) else {
+                diff.added.push(source.clone());
+                continue;
+            };
+            let existing = status
+                .import_source
+                .as_ref()
+                .map(BackupImportSource::from_import_source);
+            if existing.as_ref() == Some(source) {
+                diff.unchanged += 1;
+            } else if !status.added_by_api {
+                diff.kept.push(source.name.clone());
+            } else if let Some(existing) = existing {
+                diff.changed.push((existing, source.clone()));
+            }
+        }
+        for (name, status) in current {
+            if backup
+                .subscriptions
+                .iter()
+                .any(|source| &source.name == name)
+            {
+                continue;
+            }
+            if status.added_by_api {
+                diff.removed.push(name.clone());
+            } else {
+                diff.kept.push(name.clone());
+            }
+        }
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
@@ -0,0 +1,239 @@
+use crate::client_status::core_link::FetchedSubscription;
+use crate::client_status::storage::VersionedStore;
+use crate::grpc::proto::v2ray::core::app::subscription::SubscriptionServer;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The snapshots and the change log, so changes made by providers while no dashboard was open
+/// are recorded on the next visit.
+pub const SUBSCRIPTION_CHANGES_STORE: VersionedStore = VersionedStore {
+    key: "rendezvous.subscription_changes",
+    migrations: &[],
+};
+
+const SUBSCRIPTION_CHANGE_LIMIT: usize = 200;
+/// How long a newly added server keeps its "New" badge.
+pub const NEW_SERVER_BADGE_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum ServerChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ServerChangeKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServerChangeKind::Added => "Added",
+            ServerChangeKind::Removed => "Removed",
+            ServerChangeKind::Changed => "Changed",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ServerChange {
+    pub at: i64,
+    pub server_name: String,
+    pub display_name: String,
+    pub outbound_tag: String,
+    pub kind: ServerChangeKind,
+    pub details: Vec<String>,
+}
+
+/// The parts of a `SubscriptionServer` that are compared, in a form that can be stored.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct ServerSnapshot {
+    tag: String,
+    server_metadata: BTreeMap<String, String>,
+}
+
+impl ServerSnapshot {
+    fn from_server(server: &SubscriptionServer) -> ServerSnapshot {
+        ServerSnapshot {
+            tag: server.tag.clone(),
+            server_metadata: server
+                .server_metadata
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct SubscriptionSnapshot {
+    tag_prefix: String,
+    servers: BTreeMap<String, ServerSnapshot>,
+}
+
+impl SubscriptionSnapshot {
+    fn change(
+        &self,
+        at: i64,
+        server_name: &str,
+        server: &ServerSnapshot,
+        kind: ServerChangeKind,
+        details: Vec<String>,
+    ) -> ServerChange {
+        let outbound_tag = format!("{}_{}", self.tag_prefix, server.tag);
+        ServerChange {
+            at,
+            server_name: server_name.to_string(),
+            display_name: match server.server_metadata.get("DisplayName") {
+                Some(display_name) => display_name.clone(),
+                None => outbound_tag.clone(),
+            },
+            outbound_tag,
+            kind,
+            details,
+        }
+    }
+}
+
+fn describe_server_differences(before: &ServerSnapshot, after: &ServerSnapshot) -> Vec<String> {
+    let mut details = vec![];
+    if before.tag != after.tag {
+        details.push(format!("tag {} \u{2192} {}", before.tag, after.tag));
+    }
+    let keys: BTreeSet<&String> = before
+        .server_metadata
+        .keys()
+        .chain(after.server_metadata.keys())
+        .collect();
+    for key in keys {
+        match (before.server_metadata.get(key), after.server_metadata.get(key)) {
+            (Some(old), Some(new)) if old != new => {
+                details.push(format!("{}: {} \u{2192} {}", key, old, new))
+            }
+            (Some(_), None) => details.push(format!("{} removed", key)),
+            (None, Some(new)) => details.push(format!("{}: {}", key, new)),
+            _ => {}
+        }
+    }
+    details
+}
+
+/// Keeps the previous snapshot of every subscription so servers added, removed or changed by
+/// the provider are recorded rather than silently replaced on the next refresh. Both are kept in
+/// `SUBSCRIPTION_CHANGES_STORE` whenever they change.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SubscriptionChangeTracker {
+    snapshots: BTreeMap<String, SubscriptionSnapshot>,
+    pub changes: BTreeMap<String, Vec<ServerChange>>,
+}
+
+impl SubscriptionChangeTracker {
+    pub fn new() -> SubscriptionChangeTracker {
+        SubscriptionChangeTracker {
+            snapshots: BTreeMap::new(),
+            changes: BTreeMap::new(),
+        }
+    }
+
+    pub fn load() -> SubscriptionChangeTracker {
+        SUBSCRIPTION_CHANGES_STORE
+            .load()
+            .unwrap_or_else(SubscriptionChangeTracker::new)
+    }
+
+    /// Diffs freshly fetched subscriptions against the previous snapshots. The first snapshot
+    /// of a subscription is the baseline and records nothing; subscriptions whose content
+    /// could not be fetched keep their previous snapshot.
+    pub fn observe(&mut self, fetched_subscription: &FetchedSubscription, now: i64) {
+        let mut changed = false;
+        for (subscription_name, subscription) in fetched_subscription.managed.iter() {
+            let Some(tracked_subscription_status) = subscription else {
+                continue;
+            };
+            let snapshot = SubscriptionSnapshot {
+                tag_prefix: match &tracked_subscription_status.import_source {
+                    Some(import_source) => import_source.tag_prefix.clone(),
+                    None => "".to_string(),
+                },
+                servers: tracked_subscription_status
+                    .servers
+                    .iter()
+                    .map(|(name, server)| (name.clone(), ServerSnapshot::from_server(server)))
+                    .collect(),
+            };
+            let Some(previous) = self.snapshots.insert(subscription_name.clone(), snapshot.clone())
+            else {
+                changed = true;
+                continue;
+            };
+            changed |= previous != snapshot;
+
+            let mut changes = vec![];
+            for (server_name, server) in snapshot.servers.iter() {
+                match previous.servers.get(server_name) {
+                    None => changes.push(snapshot.change(
+                        now,
+                        server_name,
+                        server,
+                        ServerChangeKind::Added,
+                        vec![],
+                    )),
+                    Some(previous_server) if previous_server != server => changes.push(snapshot.change(
+                        now,
+                        server_name,
+                        server,
+                        ServerChangeKind::Changed,
+                        describe_server_differences(previous_server, server),
+                    )),
+                    Some(_) => {}
+                }
+            }
+            for (server_name, server) in previous.servers.iter() {
+                if !snapshot.servers.contains_key(server_name) {
+                    changes.push(previous.change(
+                        now,
+                        server_name,
+                        server,
+                        ServerChangeKind::Removed,
+                        vec![],
+                    ));
+                }
+            }
+            if changes.is_empty() {
+                continue;
+            }
+
+            let log = self.changes.entry(subscription_name.clone()).or_default();
+            log.extend(changes);
+            if log.len() > SUBSCRIPTION_CHANGE_LIMIT {
+                log.drain(..log.len() - SUBSCRIPTION_CHANGE_LIMIT);
+            }
+        }
+        if changed {
+            SUBSCRIPTION_CHANGES_STORE.save(self);
+        }
+    }
+
+    pub fn changes_for(&self, subscription_name: &str) -> &[ServerChange] {
+        match self.changes.get(subscription_name) {
+            Some(changes) => changes,
+            None => &[],
+        }
+    }
+
+    pub fn last_change_at(&self, subscription_name: &str) -> i64 {
+        match self.changes_for(subscription_name).last() {
+            Some(change) => change.at,
+            None => 0,
+        }
+    }
+
+    /// Whether the server was added by the provider within `NEW_SERVER_BADGE_SECONDS`.
+    pub fn is_new(&self, subscription_name: &str, server_name: &str, now: i64) -> bool {
+        self.changes_for(subscription_name)
+            .iter()
+            .rev()
+            .filter(|change| change.kind != ServerChangeKind::Changed)
+            .find(|change| change.server_name == server_name)
+            .is_some_and(|change| {
+                change.kind == ServerChangeKind::Added && now - change.at < NEW_SERVER_BADGE_SECONDS
+            })
+    }
+}
@@ -0,0 +1,88 @@
+use crate::client_status::outbound_handler::MANUAL_OUTBOUND_TAG_PREFIX;
+use std::collections::BTreeMap;
+
+const SUBSCRIPTION_NAME_MAX_LENGTH: usize = 64;
+
+/// Subscription names become part of the `subscription_{name}` tag prefix, so they are kept to
+/// characters that are safe in outbound tags. Names whose server tags would share the prefix of
+/// manually added outbounds are reserved.
+pub fn validate_subscription_name<T>(
+    name: &str,
+    existing: &BTreeMap<String, T>,
+) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("A name is required".to_string());
+    }
+    if name.chars().count() > SUBSCRIPTION_NAME_MAX_LENGTH {
+        return Err(format!(
+            "Use at most {} characters",
+            SUBSCRIPTION_NAME_MAX_LENGTH
+        ));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_' || *c == '.'))
+    {
+        return Err(format!(
+            "\"{}\" is not allowed; use letters, digits, \"-\", \"_\" and \".\"",
+            c
+        ));
+    }
+    if format!("subscription_{}_", name).starts_with(MANUAL_OUTBOUND_TAG_PREFIX) {
+        return Err(format!(
+            "\"{}\" is reserved for manually added servers",
+            name
+        ));
+    }
+    if existing.contains_key(name) {
+        return Err("A subscription with this name already exists".to_string());
+    }
+    Ok(())
+}
+
+/// Checks the subscription URL, returning a warning for URLs that are valid but risky.
+pub fn validate_subscription_url(url: &str) -> Result<Option<String>, String> {
+    if url.is_empty() {
+        return Err("A URL is required".to_string());
+    }
+    if url.chars().any(char::is_whitespace) {
+        return Err("The URL must not contain spaces".to_string());
+    }
+    let (scheme, rest) = url
+        .split_once("://")
+        .ok_or_else(|| "Not a URL, expected http:// or https://".to_string())?;
+    let scheme = scheme.to_lowercase();
+    if scheme != "http" && scheme != "https" {
+        return Err(format!(
+            "Unsupported scheme \"{}\", expected http or https",
+            scheme
+        ));
+    }
+    let authority = rest
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    let (credentials, host) = match authority.rsplit_once('@') {
+        Some((credentials, host)) => (Some(credentials), host),
+        None => (None, authority),
+    };
+    let host_name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    if host_name.is_empty() {
+        return Err("The URL has no host".to_string());
+    }
+    if credentials.is_some() {
+        return Ok(Some(
+            "The URL embeds credentials, which are stored in the core configuration and shown in this UI"
+                .to_string(),
+        ));
+    }
+    if scheme == "http" {
+        return Ok(Some(
+            "The subscription will be fetched over plain HTTP".to_string(),
+        ));
+    }
+    Ok(None)
+}
@@ -0,0 +1,168 @@
+use crate::client_status::storage::VersionedStore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Scheduled updates are spaced at least this far apart, so subscriptions sharing an interval do
+/// not all fetch through `import_using_tag` at once.
+pub const REFRESH_STAGGER_SECONDS: i64 = 30;
+
+/// The intervals offered in the UI, in minutes.
+pub const REFRESH_INTERVAL_CHOICES: [u32; 7] = [15, 30, 60, 180, 360, 720, 1440];
+
+/// Auto-refresh interval per subscription name, in minutes. Subscriptions without an entry are
+/// only updated manually.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SubscriptionRefreshSettings {
+    pub intervals: BTreeMap<String, u32>,
+}
+
+impl SubscriptionRefreshSettings {
+    pub fn interval_seconds(&self, name: &str) -> Option<i64> {
+        self.intervals
+            .get(name)
+            .filter(|minutes| **minutes > 0)
+            .map(|minutes| *minutes as i64 * 60)
+    }
+
+    pub fn with_interval(&self, name: &str, minutes: Option<u32>) -> SubscriptionRefreshSettings {
+        let mut intervals = self.intervals.clone();
+        match minutes {
+            Some(minutes) if minutes > 0 => intervals.insert(name.to_string(), minutes),
+            _ => intervals.remove(name),
+        };
+        SubscriptionRefreshSettings { intervals }
+    }
+}
+
+pub fn describe_interval_minutes(minutes: u32) -> String {
+    match minutes {
+        m if m % 60 == 0 && m >= 60 => format!("Every {}h", m / 60),
+        m => format!("Every {}m", m),
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct RefreshRun {
+    pub at: i64,
+    pub result: Result<(), String>,
+}
+
+/// Shared by every open tab, so reloads keep the schedule and tabs do not update the same
+/// subscriptions twice.
+pub const SUBSCRIPTION_REFRESH_STORE: VersionedStore = VersionedStore {
+    key: "rendezvous.subscription_refresh",
+    migrations: &[],
+};
+
+/// A claim left behind by a tab closed during an update is ignored after this long.
+const CLAIM_TIMEOUT_SECONDS: i64 = 10 * 60;
+
+/// When each subscription was last updated by the scheduler, and when the scheduler first saw it.
+/// The reducer reloads it from `SUBSCRIPTION_REFRESH_STORE` before picking an update and saves
+/// the claim before starting it, so neither a second poll nor another tab picks the same one.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct SubscriptionRefreshState {
+    first_seen: BTreeMap<String, i64>,
+    pub last_runs: BTreeMap<String, RefreshRun>,
+    /// Updates in progress in any tab, with when they were claimed.
+    pub running: BTreeMap<String, i64>,
+    last_call_at: i64,
+}
+
+impl Default for SubscriptionRefreshState {
+    fn default() -> Self {
+        SubscriptionRefreshState::new()
+    }
+}
+
+/// A stable offset into the interval, so the first run of each subscription lands at a
+/// different point rather than all at once when the page opens.
+fn stagger_offset(name: &str, interval_seconds: i64) -> i64 {
+    let hash = name.bytes().fold(2166136261u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(16777619)
+    });
+    hash as i64 % interval_seconds.max(1)
+}
+
+impl SubscriptionRefreshState {
+    pub fn new() -> SubscriptionRefreshState {
+        SubscriptionRefreshState {
+            first_seen: BTreeMap::new(),
+            last_runs: BTreeMap::new(),
+            running: BTreeMap::new(),
+            last_call_at: 0,
+        }
+    }
+
+    pub fn load() -> SubscriptionRefreshState {
+        SUBSCRIPTION_REFRESH_STORE.load().unwrap_or_default()
+    }
+
+    /// The state as last saved by any tab. `self` is kept when nothing readable is stored.
+    pub fn reload(&self) -> SubscriptionRefreshState {
+        SUBSCRIPTION_REFRESH_STORE
+            .load()
+            .unwrap_or_else(|| self.clone())
+    }
+
+    pub fn save(&self) {
+        SUBSCRIPTION_REFRESH_STORE.save(self);
+    }
+
+    pub fn is_running(&self, name: &str, now: i64) -> bool {
+        self.running
+            .get(name)
+            .is_some_and(|claimed_at| now - claimed_at < CLAIM_TIMEOUT_SECONDS)
+    }
+
+    pub fn next_run_at(&self, settings: &SubscriptionRefreshSettings, name: &str) -> Option<i64> {
+        let interval = settings.interval_seconds(name)?;
+        match self.last_runs.get(name) {
+            Some(run) => Some(run.at + interval),
+            None => {
+                let first_seen = *self.first_seen.get(name)?;
+                Some(first_seen + stagger_offset(name, interval))
+            }
+        }
+    }
+
+    /// Picks the most overdue subscription to update now, if any, keeping calls at least
+    /// `REFRESH_STAGGER_SECONDS` apart.
+    pub fn due<'a>(
+        &mut self,
+        settings: &SubscriptionRefreshSettings,
+        names: impl Iterator<Item = &'a String>,
+        now: i64,
+    ) -> Option<String> {
+        let mut due: Option<(i64, String)> = None;
+        for name in names {
+            if settings.interval_seconds(name).is_none() || self.is_running(name, now) {
+                continue;
+            }
+            self.first_seen.entry(name.clone()).or_insert(now);
+            let Some(next_run_at) = self.next_run_at(settings, name) else {
+                continue;
+            };
+            if next_run_at <= now && due.as_ref().map_or(true, |(at, _)| next_run_at < *at) {
+                due = Some((next_run_at, name.clone()));
+            }
+        }
+        if now - self.last_call_at < REFRESH_STAGGER_SECONDS {
+            return None;
+        }
+        due.map(|(_, name)| name)
+    }
+
+    /// Marks the update as started, which also starts the stagger wait.
+    pub fn claim(&mut self, name: &str, now: i64) {
+        self.last_call_at = now;
+        self.running.insert(name.to_string(), now);
+    }
+
+    pub fn record(&mut self, name: String, run: RefreshRun) {
+        self.running.remove(&name);
+        self.last_runs.insert(name, run);
+    }
+}
@@ -0,0 +1,70 @@
+/// Traffic and expiry a provider reports in the `subscription-userinfo` response header, such as
+/// `upload=0; download=1024; total=10240; expire=1700000000`. Cross-origin providers have to
+/// expose the header for the browser to see it.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct SubscriptionUsage {
+    pub used_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    /// Unix seconds.
+    pub expires_at: Option<i64>,
+}
+
+pub fn parse_subscription_userinfo(header: &str) -> Option<SubscriptionUsage> {
+    let mut upload = None;
+    let mut download = None;
+    let mut usage = SubscriptionUsage::default();
+    for field in header.split(';') {
+        let Some((key, value)) = field.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim().to_lowercase().as_str() {
+            "upload" => upload = value.parse::<u64>().ok(),
+            "download" => download = value.parse::<u64>().ok(),
+            "total" => usage.total_bytes = value.parse::<u64>().ok().filter(|total| *total > 0),
+            "expire" => usage.expires_at = value.parse::<i64>().ok().filter(|at| *at > 0),
+            _ => {}
+        }
+    }
+    usage.used_bytes = match (upload, download) {
+        (None, None) => None,
+        (upload, download) => Some(upload.unwrap_or(0).saturating_add(download.unwrap_or(0))),
+    };
+    if usage == SubscriptionUsage::default() {
+        return None;
+    }
+    Some(usage)
+}
+
+impl SubscriptionUsage {
+    pub fn remaining_seconds(&self, now: i64) -> Option<i64> {
+        self.expires_at.map(|expires_at| expires_at - now)
+    }
+
+    pub fn describe_traffic(&self) -> Option<String> {
+        match (self.used_bytes, self.total_bytes) {
+            (Some(used), Some(total)) => Some(format!(
+                "{} of {} used",
+                format_bytes(used),
+                format_bytes(total)
+            )),
+            (Some(used), None) => Some(format!("{} used", format_bytes(used))),
+            (None, Some(total)) => Some(format!("{} total", format_bytes(total))),
+            (None, None) => None,
+        }
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} {}", bytes, UNITS[0]),
+        _ => format!("{:.1} {}", value, UNITS[unit]),
+    }
+}
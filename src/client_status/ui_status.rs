@@ -1,12 +1,127 @@
+use crate::client_status::route::Route;
+use crate::client_status::storage::VersionedStore;
+use crate::client_status::subscription_backup::BackupImportSource;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub const UI_STATUS_STORE: VersionedStore = VersionedStore {
+    key: "rendezvous.ui_status",
+    migrations: &[],
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum ObservatorySortKey {
+    OutboundTag,
+    Alive,
+    Delay,
+    LastSeen,
+    LastTry,
+}
+
+/// Subscriptions the core removed, kept so the removal can be undone for a short while.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct RemovedSubscription {
+    pub sources: Vec<BackupImportSource>,
+    pub removed_at: i64,
+}
+
+/// Kept across reloads so the page, open panels and form drafts survive. Confirmations, revealed
+/// secrets and other one-off state are skipped.
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
 pub struct UIStatus {
     #[serde()]
-    pub(crate) active_window: String,
+    pub(crate) route: Route,
     #[serde()]
     pub(crate) subscription_add_new_card_open: bool,
     #[serde()]
     pub(crate) subscription_add_new_name: String,
     #[serde()]
     pub(crate) subscription_add_new_url: String,
+    #[serde()]
+    pub(crate) subscription_preview_open: bool,
+    #[serde()]
+    pub(crate) subscription_preview_document: String,
+    #[serde()]
+    pub(crate) subscription_expanded: BTreeSet<String>,
+    #[serde()]
+    pub(crate) subscription_selected: BTreeSet<String>,
+    #[serde()]
+    pub(crate) subscription_backup_card_open: bool,
+    #[serde(skip)]
+    pub(crate) subscription_remove_pending: BTreeSet<String>,
+    #[serde(skip)]
+    pub(crate) subscription_removed: Option<RemovedSubscription>,
+    #[serde()]
+    pub(crate) subscription_focused_outbound: String,
+    #[serde()]
+    pub(crate) observatory_sort_key: ObservatorySortKey,
+    #[serde()]
+    pub(crate) observatory_sort_descending: bool,
+    #[serde()]
+    pub(crate) watchdog_acknowledged_at: i64,
+    #[serde()]
+    pub(crate) server_details_expanded: BTreeSet<String>,
+    #[serde(skip)]
+    pub(crate) server_secrets_revealed: BTreeSet<String>,
+    #[serde(skip)]
+    pub(crate) server_export_outbound: String,
+    #[serde()]
+    pub(crate) manual_outbound_card_open: bool,
+    #[serde()]
+    pub(crate) manual_outbound_new_link: String,
+    #[serde()]
+    pub(crate) manual_outbound_new_name: String,
+    /// The tag of the last outbound the core refused to add, with the reason.
+    #[serde(skip)]
+    pub(crate) manual_outbound_error: Option<(String, String)>,
+    /// The tag of the last outbound the core failed to remove, with the reason.
+    #[serde(skip)]
+    pub(crate) manual_outbound_remove_error: Option<(String, String)>,
+}
+
+impl Default for UIStatus {
+    fn default() -> Self {
+        UIStatus {
+            route: Route::default(),
+            subscription_add_new_card_open: false,
+            subscription_add_new_name: String::from(""),
+            subscription_add_new_url: String::from(""),
+            subscription_preview_open: false,
+            subscription_preview_document: String::from(""),
+            subscription_expanded: BTreeSet::new(),
+            subscription_selected: BTreeSet::new(),
+            subscription_backup_card_open: false,
+            subscription_remove_pending: BTreeSet::new(),
+            subscription_removed: None,
+            subscription_focused_outbound: String::from(""),
+            observatory_sort_key: ObservatorySortKey::OutboundTag,
+            observatory_sort_descending: false,
+            watchdog_acknowledged_at: 0,
+            server_details_expanded: BTreeSet::new(),
+            server_secrets_revealed: BTreeSet::new(),
+            server_export_outbound: String::from(""),
+            manual_outbound_card_open: false,
+            manual_outbound_new_link: String::from(""),
+            manual_outbound_new_name: String::from(""),
+            manual_outbound_error: None,
+            manual_outbound_remove_error: None,
+        }
+    }
+}
+
+impl UIStatus {
+    /// The stored status, with the route taken from the location when it has one, so links and
+    /// bookmarks win over the page that was open last.
+    pub fn load() -> UIStatus {
+        let ui_status: UIStatus = UI_STATUS_STORE.load().unwrap_or_default();
+        match Route::from_location() {
+            Some(route) => UIStatus { route, ..ui_status },
+            None => ui_status,
+        }
+    }
+
+    pub fn save(&self) {
+        UI_STATUS_STORE.save(self);
+    }
 }
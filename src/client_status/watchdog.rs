@@ -0,0 +1,132 @@
+use crate::client_status::core_link::FetchedMeasurement;
+use serde::{Deserialize, Serialize};
+
+const WATCHDOG_EVENT_LIMIT: usize = 50;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct WatchdogSettings {
+    pub enabled: bool,
+    pub failure_threshold: u32,
+    pub fallback_targets: Vec<String>,
+}
+
+impl Default for WatchdogSettings {
+    fn default() -> Self {
+        WatchdogSettings {
+            enabled: false,
+            failure_threshold: 3,
+            fallback_targets: vec![],
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum WatchdogAction {
+    ClearOverride,
+    SwitchTo(String),
+}
+
+impl WatchdogAction {
+    pub fn balancer_target(&self) -> String {
+        match self {
+            WatchdogAction::ClearOverride => "".to_string(),
+            WatchdogAction::SwitchTo(target) => target.clone(),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct WatchdogEvent {
+    pub at: i64,
+    pub failed_target: String,
+    pub consecutive_failures: u32,
+    pub action: WatchdogAction,
+}
+
+impl WatchdogEvent {
+    pub fn describe(&self) -> String {
+        match &self.action {
+            WatchdogAction::ClearOverride => format!(
+                "{} was dead for {} polls, the manual override was cleared",
+                self.failed_target, self.consecutive_failures
+            ),
+            WatchdogAction::SwitchTo(target) => format!(
+                "{} was dead for {} polls, switched to {}",
+                self.failed_target, self.consecutive_failures, target
+            ),
+        }
+    }
+}
+
+/// Tracks how long the manually selected outbound has been reported dead.
+#[derive(PartialEq, Debug, Clone)]
+pub struct WatchdogState {
+    pub watched_target: String,
+    pub consecutive_failures: u32,
+    pub events: Vec<WatchdogEvent>,
+}
+
+impl WatchdogState {
+    pub fn new() -> WatchdogState {
+        WatchdogState {
+            watched_target: "".to_string(),
+            consecutive_failures: 0,
+            events: vec![],
+        }
+    }
+
+    /// Feeds one poll into the watchdog, returning the event to act on once the
+    /// override target has been dead for `failure_threshold` consecutive polls.
+    pub fn observe(
+        &mut self,
+        settings: &WatchdogSettings,
+        override_target: Option<String>,
+        measurement: &FetchedMeasurement,
+        now: i64,
+    ) -> Option<WatchdogEvent> {
+        let override_target = match override_target {
+            Some(target) if settings.enabled => target,
+            _ => {
+                self.watched_target = "".to_string();
+                self.consecutive_failures = 0;
+                return None;
+            }
+        };
+        if self.watched_target != override_target {
+            self.watched_target = override_target.clone();
+            self.consecutive_failures = 0;
+        }
+
+        match measurement.managed.get(&override_target) {
+            Some(status) if !status.alive => self.consecutive_failures += 1,
+            _ => self.consecutive_failures = 0,
+        }
+        if self.consecutive_failures < settings.failure_threshold.max(1) {
+            return None;
+        }
+
+        let fallback = settings.fallback_targets.iter().find(|target| {
+            **target != override_target
+                && measurement
+                    .managed
+                    .get(*target)
+                    .is_some_and(|status| status.alive)
+        });
+        let event = WatchdogEvent {
+            at: now,
+            failed_target: override_target,
+            consecutive_failures: self.consecutive_failures,
+            action: match fallback {
+                Some(target) => WatchdogAction::SwitchTo(target.clone()),
+                None => WatchdogAction::ClearOverride,
+            },
+        };
+        self.consecutive_failures = 0;
+        self.events.push(event.clone());
+        if self.events.len() > WATCHDOG_EVENT_LIMIT {
+            self.events.remove(0);
+        }
+        Some(event)
+    }
+}
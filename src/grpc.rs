@@ -10,6 +10,7 @@ use crate::grpc::proto::v2ray::core::app::observatory::observatory_command::GetO
 pub mod proto {
     pub mod v2ray {
         pub mod core {
+            tonic::include_proto!("v2ray.core");
             pub mod common {
                 pub mod protoext {
                     tonic::include_proto!("v2ray.core.common.protoext");
@@ -17,6 +18,51 @@ pub mod proto {
                 pub mod net {
                     tonic::include_proto!("v2ray.core.common.net");
                 }
+                pub mod protocol {
+                    tonic::include_proto!("v2ray.core.common.protocol");
+                }
+            }
+            pub mod net {
+                pub mod packetaddr {
+                    tonic::include_proto!("v2ray.core.net.packetaddr");
+                }
+            }
+            pub mod transport {
+                tonic::include_proto!("v2ray.core.transport");
+                pub mod internet {
+                    tonic::include_proto!("v2ray.core.transport.internet");
+                    pub mod tls {
+                        tonic::include_proto!("v2ray.core.transport.internet.tls");
+                    }
+                    pub mod websocket {
+                        tonic::include_proto!("v2ray.core.transport.internet.websocket");
+                    }
+                    pub mod grpc {
+                        pub mod encoding {
+                            tonic::include_proto!("v2ray.core.transport.internet.grpc.encoding");
+                        }
+                    }
+                }
+            }
+            pub mod proxy {
+                pub mod vmess {
+                    tonic::include_proto!("v2ray.core.proxy.vmess");
+                    pub mod outbound {
+                        tonic::include_proto!("v2ray.core.proxy.vmess.outbound");
+                    }
+                }
+                pub mod vless {
+                    tonic::include_proto!("v2ray.core.proxy.vless");
+                    pub mod outbound {
+                        tonic::include_proto!("v2ray.core.proxy.vless.outbound");
+                    }
+                }
+                pub mod trojan {
+                    tonic::include_proto!("v2ray.core.proxy.trojan");
+                }
+                pub mod shadowsocks {
+                    tonic::include_proto!("v2ray.core.proxy.shadowsocks");
+                }
             }
             pub mod app {
                 pub mod observatory {
@@ -40,6 +86,12 @@ pub mod proto {
                         tonic::include_proto!("v2ray.core.app.router.command");
                     }
                 }
+                pub mod proxyman {
+                    tonic::include_proto!("v2ray.core.app.proxyman");
+                    pub mod command {
+                        tonic::include_proto!("v2ray.core.app.proxyman.command");
+                    }
+                }
             }
         }
     }